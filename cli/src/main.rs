@@ -6,13 +6,15 @@ extern crate clap;
 extern crate serde_json;
 
 use std::env;
-use walmart_partner_api::{Client, WalmartCredential, WalmartMarketplace};
+use std::sync::Arc;
+use walmart_partner_api::{Client, FileTokenStore, WalmartCredential, WalmartMarketplace};
 
 mod feed;
 mod inventory;
 mod item;
 mod order;
 mod report;
+mod returns;
 
 fn main() {
   let matches = clap_app!(cli =>
@@ -64,15 +66,30 @@ fn main() {
         (@arg method: -m --method_code +takes_value "Sets the method code, default 'Standard'")
         (@arg other_carrier: -o --other_carrier +takes_value "Sets the otherCarrier")
         (@arg unit_of_measurement: -u --unit_of_measurement +takes_value "Sets the unitOfMeasurement")
-        (@arg amount: -a --amount +takes_value "Sets the amount")
+        (@arg amount: -a --amount +takes_value +multiple number_of_values(1) "Sets the units of each package, default all remaining units")
         (@arg carrier_name: -c --carrier_name +takes_value "Sets the carrier name")
-        (@arg tracking_number: -t --tracking_number +takes_value "Sets the tracking number")
-        (@arg tracking_url: -r --tracking_url +takes_value "Sets the tracking url")
+        (@arg tracking_number: -t --tracking_number +takes_value +required +multiple number_of_values(1) "Sets the tracking number of each package")
+        (@arg tracking_url: -r --tracking_url +takes_value "Sets the tracking url, default derived from the carrier")
+        (@arg shipFromCountry: -f --ship_from_country +takes_value "Sets the ship from country, default 'USA'")
+        (@arg no_validate: --no_validate "Skips the tracking number format check")
       )
       (@subcommand ack =>
         (about: "ack order")
         (@arg PO_ID: +required "Sets the po id")
       )
+      (@subcommand ack_all =>
+        (about: "ack all released orders")
+        (@arg max_parallel: -p --max_parallel +takes_value "Sets the max concurrent requests, default 4")
+        (@arg dry_run: --("dry-run") "Lists the released orders without acking")
+      )
+      (@subcommand cancel =>
+        (about: "cancel order lines")
+        (@arg ORDER_ID: +required "Sets the order id")
+        (@arg LINE_NUMBER: +required +multiple "Sets the line numbers")
+        (@arg reason: -r --reason +takes_value "Sets the cancellation reason, default 'SELLER_CANCEL_OUT_OF_STOCK'")
+        (@arg unit_of_measurement: -u --unit_of_measurement +takes_value "Sets the unitOfMeasurement")
+        (@arg amount: -a --amount +takes_value "Sets the amount")
+      )
     )
     (@subcommand report =>
       (about: "Report API")
@@ -91,8 +108,24 @@ fn main() {
       (@subcommand dump =>
         (about: "dump items")
       )
-      (@subcommand dump_us =>
-        (about: "dump us items")
+    )
+    (@subcommand returns =>
+      (about: "Returns API")
+      (@subcommand list =>
+        (about: "list returns")
+        (@arg days: -d --days +takes_value "Sets the days since the return was created, default 30")
+        (@arg customer_order_id: -c --customer_order_id +takes_value "Sets the customer order id")
+        (@arg status: -s --status +takes_value "Sets the return line status")
+      )
+      (@subcommand get =>
+        (about: "get return")
+        (@arg RETURN_ORDER_ID: +required "Sets the return order id")
+      )
+      (@subcommand refund =>
+        (about: "refund return lines")
+        (@arg RETURN_ORDER_ID: +required "Sets the return order id")
+        (@arg LINE_NUMBER: +required +multiple "Sets the return order line numbers")
+        (@arg quantity: -q --quantity +takes_value "Sets the quantity of every line, default 1")
       )
     )
     (@subcommand inventory =>
@@ -122,7 +155,7 @@ fn main() {
 
   env_logger::init();
 
-  let client = Client::new(
+  let mut builder = Client::builder(
     match env::var("WALMART_MARKETPLACE").unwrap().as_ref() {
       "USA" => WalmartMarketplace::USA,
       "Canada" => WalmartMarketplace::Canada,
@@ -140,8 +173,17 @@ fn main() {
         private_key: env::var("WALMART_PRIVATE_KEY").unwrap(),
       }
     },
-  )
-  .unwrap();
+  );
+
+  if let Ok(base_url) = env::var("WALMART_BASE_URL") {
+    builder = builder.base_url(&base_url);
+  }
+
+  if let Ok(path) = env::var("WALMART_TOKEN_FILE") {
+    builder = builder.token_store(Arc::new(FileTokenStore::new(path)));
+  }
+
+  let client = builder.build().unwrap();
 
   match matches.subcommand() {
     ("feed", Some(matches)) => match matches.subcommand() {
@@ -185,6 +227,12 @@ fn main() {
       ("ack", Some(m)) => {
         order::ack(&client, m.value_of("PO_ID").unwrap());
       }
+      ("ack_all", Some(m)) => {
+        order::ack_all(&client, m);
+      }
+      ("cancel", Some(m)) => {
+        order::cancel(&client, m);
+      }
       _ => {}
     },
     ("report", Some(matches)) => match matches.subcommand() {
@@ -200,12 +248,20 @@ fn main() {
       }
       _ => {}
     },
-    ("item", Some(matches)) => match matches.subcommand() {
-      ("dump", _) => {
+    ("item", Some(matches)) => {
+      if let ("dump", _) = matches.subcommand() {
         item::dump(&client);
       }
-      ("dump_us", _) => {
-        item::dump_us(&client);
+    }
+    ("returns", Some(matches)) => match matches.subcommand() {
+      ("list", Some(m)) => {
+        returns::list(&client, m);
+      }
+      ("get", Some(m)) => {
+        returns::get(&client, m.value_of("RETURN_ORDER_ID").unwrap());
+      }
+      ("refund", Some(m)) => {
+        returns::refund(&client, m);
       }
       _ => {}
     },
@@ -9,7 +9,11 @@ pub fn list(client: &Client, status: Option<&str>) {
   let start_date = (Utc::now() - Duration::days(7)).date().and_hms(0, 0, 0);
   query.createdStartDate = Some(start_date);
   query.createdEndDate = Some(Utc::now());
-  query.status = status.or(Some("Created")).map(|s| s.to_owned());
+  query.status = Some(
+    status
+      .map(OrderLineStatusKind::from)
+      .unwrap_or(OrderLineStatusKind::Created),
+  );
   let res = client.get_all_orders(&query).unwrap();
   println!("{:#?}", res);
 }
@@ -18,7 +22,7 @@ pub fn list_wfs(client: &Client, status: Option<&str>) {
   let mut query: WFSQueryParams = Default::default();
   let start_date = (Utc::now() - Duration::days(7));
   query.createdStartDate = Some(start_date);
-  query.status = status.map(|s| s.to_owned());
+  query.status = status.map(OrderLineStatusKind::from);
   let res = client.get_all_wfs_orders(&query).unwrap();
   println!("{:#?}", res);
 }
@@ -33,7 +37,7 @@ pub fn list_status(client: &Client, status: &str) {
   let mut query: QueryParams = Default::default();
   let start_date = (Utc::now() - Duration::days(30)).date().and_hms(0, 0, 0);
   query.createdStartDate = Some(start_date);
-  query.status = Some(status.to_string());
+  query.status = Some(OrderLineStatusKind::from(status));
   let res = client.get_all_orders(&query).unwrap();
   println!("{:#?}", res);
 }
@@ -44,22 +48,11 @@ pub fn dump(client: &Client) {
   let start_date = (Utc::now() - Duration::days(100)).date().and_hms(0, 0, 0);
   query.createdStartDate = Some(start_date);
 
-  let res = client.get_all_orders(&query).unwrap();
-
-  let mut next_cursor = res.get_next_cursor().map(str::to_string);
-  let mut elements = res.elements;
-  while let Some(cursor) = next_cursor {
-    println!("next_cursor = {:?}", cursor);
-    let mut res = client.get_all_orders_by_next_cursor(&cursor).unwrap();
-    next_cursor = res.get_next_cursor().map(str::to_string);
-    // println!(
-    //   "n = {:?}, next_cursur = {:?}",
-    //   res.get_total_count(),
-    //   next_cursor
-    // );
-    elements.append(&mut res.elements);
-    ::std::thread::sleep(::std::time::Duration::from_secs(1));
-  }
+  let elements: Vec<Order> = client
+    .orders(&query)
+    .items()
+    .collect::<Result<_, _>>()
+    .unwrap();
 
   println!("{}", serde_json::to_string_pretty(&elements).unwrap());
 }
@@ -70,35 +63,83 @@ pub fn get(client: &Client, id: &str) {
 }
 
 pub fn ship(client: &Client, m: &ArgMatches) {
-  let params = ShipParams {
-    lineNumber: m
-      .value_of("line_number")
-      .map(ToString::to_string)
-      .unwrap_or_else(|| "1".to_string()),
-    shipDateTime: Utc::now(),
-    carrierName: m.value_of("carrier_name").map(ToString::to_string),
-    methodCode: m
-      .value_of("method")
-      .map(ToString::to_string)
-      .unwrap_or_else(|| "Standard".to_string()),
-    trackingNumber: m
-      .value_of("tracking_number")
-      .map(ToString::to_string)
-      .unwrap(),
-    trackingURL: m
-      .value_of("tracking_url")
-      .map(ToString::to_string)
-      .unwrap_or_default(),
-    otherCarrier: m.value_of("other_carrier").map(ToString::to_string),
-    unitOfMeasurement: m.value_of("unit_of_measurement").map(ToString::to_string),
-    amount: m.value_of("amount").map(ToString::to_string),
+  let po_id = m.value_of("ORDER_ID").unwrap();
+  let line_number = m
+    .value_of("line_number")
+    .map(ToString::to_string)
+    .unwrap_or_else(|| "1".to_string());
+  let tracking_numbers: Vec<&str> = m.values_of("tracking_number").unwrap().collect();
+  let amounts: Vec<u32> = match m.values_of("amount") {
+    Some(amounts) => amounts.map(|amount| amount.parse().unwrap()).collect(),
+    // a single package contains all remaining units
+    None if tracking_numbers.len() == 1 => {
+      let order = client.get_order(po_id).unwrap();
+      vec![order
+        .get_line(&line_number)
+        .unwrap()
+        .remaining_to_ship()
+        .unwrap()]
+    }
+    None => panic!("--amount is required for every package"),
+  };
+  assert_eq!(
+    amounts.len(),
+    tracking_numbers.len(),
+    "every package requires a tracking number and an amount"
+  );
+
+  let packages = tracking_numbers
+    .into_iter()
+    .zip(amounts)
+    .map(|(tracking_number, amount)| ShipPackage {
+      amount,
+      unitOfMeasurement: m.value_of("unit_of_measurement").map(ToString::to_string),
+      shipDateTime: Utc::now(),
+      carrierName: m.value_of("carrier_name").map(ToString::to_string),
+      otherCarrier: m.value_of("other_carrier").map(ToString::to_string),
+      methodCode: m
+        .value_of("method")
+        .map(ToString::to_string)
+        .unwrap_or_else(|| "Standard".to_string()),
+      trackingNumber: tracking_number.to_string(),
+      trackingURL: m
+        .value_of("tracking_url")
+        .map(ToString::to_string)
+        .unwrap_or_default(),
+    })
+    .collect();
+  let shipment = LineShipment {
+    lineNumber: line_number,
     shipFromCountry: m
       .value_of("shipFromCountry")
       .map(ToString::to_string)
-      .unwrap(),
+      .unwrap_or_else(|| "USA".to_string()),
+    packages,
   };
+  if !m.is_present("no_validate") {
+    shipment.validate().unwrap();
+  }
+  let res = client.ship_order_packages(po_id, &[shipment]).unwrap();
+  println!("{}", serde_json::to_string_pretty(&res).unwrap());
+}
+
+pub fn cancel(client: &Client, m: &ArgMatches) {
+  let reason = m
+    .value_of("reason")
+    .map(CancellationReason::from)
+    .unwrap_or(CancellationReason::SellerCancelOutOfStock);
+  let lines: Vec<CancelParams> = m
+    .values_of("LINE_NUMBER")
+    .unwrap()
+    .map(|line_number| CancelParams {
+      lineNumber: line_number.to_string(),
+      cancellationReason: reason.clone(),
+      unitOfMeasurement: m.value_of("unit_of_measurement").map(ToString::to_string),
+      amount: m.value_of("amount").map(ToString::to_string),
+    })
+    .collect();
   let res = client
-    .ship_order_line(m.value_of("ORDER_ID").unwrap(), &params)
+    .cancel_order_lines(m.value_of("ORDER_ID").unwrap(), &lines)
     .unwrap();
   println!("{}", serde_json::to_string_pretty(&res).unwrap());
 }
@@ -107,3 +148,33 @@ pub fn ack(client: &Client, po_id: &str) {
   let res = client.ack_order(po_id).unwrap();
   println!("{}", serde_json::to_string_pretty(&res).unwrap());
 }
+
+pub fn ack_all(client: &Client, m: &ArgMatches) {
+  if m.is_present("dry_run") {
+    let query = ReleasedQueryParams {
+      limit: Some(200),
+      ..Default::default()
+    };
+    for order in client.released_orders(&query).items() {
+      println!("{}: would ack", order.unwrap().purchaseOrderId);
+    }
+    return;
+  }
+
+  let max_parallel = m
+    .value_of("max_parallel")
+    .map(|v| v.parse().unwrap())
+    .unwrap_or(4);
+  let results = client.ack_all_released(max_parallel).unwrap();
+  let mut failed = 0;
+  for r in &results {
+    match r.result {
+      Ok(_) => println!("{}: acked", r.purchase_order_id),
+      Err(ref err) => {
+        failed += 1;
+        println!("{}: failed: {}", r.purchase_order_id, err);
+      }
+    }
+  }
+  println!("{} acked, {} failed", results.len() - failed, failed);
+}
@@ -0,0 +1,47 @@
+use chrono::{Duration, Utc};
+use clap::ArgMatches;
+use walmart_partner_api::returns::*;
+use walmart_partner_api::Client;
+
+pub fn list(client: &Client, m: &ArgMatches) {
+  let mut query: ReturnsQueryParams = Default::default();
+  let days: i64 = m.value_of("days").map(|v| v.parse().unwrap()).unwrap_or(30);
+  query.returnCreationStartDate = Some(Utc::now() - Duration::days(days));
+  query.customerOrderId = m.value_of("customer_order_id").map(ToString::to_string);
+  query.status = m.value_of("status").map(ToString::to_string);
+  query.limit = Some(200);
+
+  let elements: Vec<ReturnOrder> = client
+    .returns(&query)
+    .items()
+    .collect::<Result<_, _>>()
+    .unwrap();
+
+  println!("{}", serde_json::to_string_pretty(&elements).unwrap());
+}
+
+pub fn get(client: &Client, id: &str) {
+  let res = client.get_return(id).unwrap();
+  println!("{}", serde_json::to_string_pretty(&res).unwrap());
+}
+
+pub fn refund(client: &Client, m: &ArgMatches) {
+  let id = m.value_of("RETURN_ORDER_ID").unwrap();
+  let quantity: u32 = m
+    .value_of("quantity")
+    .map(|v| v.parse().unwrap())
+    .unwrap_or(1);
+  let order = client.get_return(id).unwrap();
+  let lines: Vec<ReturnRefundLine> = m
+    .values_of("LINE_NUMBER")
+    .unwrap()
+    .map(|line_number| ReturnRefundLine {
+      returnOrderLineNumber: line_number.parse().unwrap(),
+      quantity,
+    })
+    .collect();
+  let res = client
+    .refund_return(id, &order.customerOrderId, &lines)
+    .unwrap();
+  println!("{}", serde_json::to_string_pretty(&res).unwrap());
+}
@@ -0,0 +1,106 @@
+//! Token API and signature authentication
+
+use crate::error_response;
+use crate::http::{Request, Response};
+use crate::state::State;
+use reqwest::Method;
+use walmart_partner_api::Signature;
+
+const TOKEN_EXPIRES_IN: u64 = 900;
+
+pub struct Auth {
+  token_api: Option<(String, String)>,
+  signature: Option<Signature>,
+}
+
+impl Auth {
+  pub fn new(token_api: Option<(String, String)>, signature: Option<Signature>) -> Self {
+    Auth {
+      token_api,
+      signature,
+    }
+  }
+
+  /// `POST /v3/token`
+  pub fn issue_token(&self, state: &mut State, req: &Request) -> Response {
+    if !self.check_basic_auth(req) {
+      return unauthorized("invalid client credentials");
+    }
+
+    let access_token = format!("mock-access-token-{}", state.next_id());
+    state.tokens.insert(access_token.clone());
+    Response::json(
+      200,
+      &json!({
+        "access_token": access_token,
+        "token_type": "Bearer",
+        "expires_in": TOKEN_EXPIRES_IN,
+      }),
+    )
+  }
+
+  /// Checks the access token or the signature of an API request
+  pub fn check(&self, state: &State, base_url: &str, req: &Request) -> Result<(), Response> {
+    if let Some(access_token) = req.header("wm_sec.access_token") {
+      if !self.check_basic_auth(req) {
+        return Err(unauthorized("invalid client credentials"));
+      }
+      if !state.tokens.contains(access_token) {
+        return Err(unauthorized("invalid or expired access token"));
+      }
+      return Ok(());
+    }
+
+    if let Some(signature) = req.header("wm_sec.auth_signature") {
+      return self.check_signature(base_url, req, signature);
+    }
+
+    Err(unauthorized("missing authentication headers"))
+  }
+
+  fn check_basic_auth(&self, req: &Request) -> bool {
+    let (client_id, client_secret) = match self.token_api {
+      Some((ref client_id, ref client_secret)) => (client_id, client_secret),
+      None => return false,
+    };
+    let expected = format!(
+      "Basic {}",
+      base64::encode(&format!("{}:{}", client_id, client_secret))
+    );
+    req.header("authorization") == Some(expected.as_str())
+  }
+
+  fn check_signature(
+    &self,
+    base_url: &str,
+    req: &Request,
+    signature: &str,
+  ) -> Result<(), Response> {
+    let verifier = self
+      .signature
+      .as_ref()
+      .ok_or_else(|| unauthorized("signature authentication is not enabled"))?;
+
+    if req.header("wm_consumer.id") != Some(verifier.consumer_id()) {
+      return Err(unauthorized("invalid consumer id"));
+    }
+    let timestamp = req
+      .header("wm_sec.timestamp")
+      .and_then(|v| v.parse::<i64>().ok())
+      .ok_or_else(|| unauthorized("invalid timestamp"))?;
+    let method =
+      Method::from_bytes(req.method.as_bytes()).map_err(|_| unauthorized("invalid method"))?;
+
+    // the client signs the full url it sends the request to
+    let url = format!("{}{}", base_url, req.target);
+    match verifier.verify(&url, method, timestamp, signature) {
+      Ok(true) => Ok(()),
+      Ok(false) => Err(unauthorized("invalid signature")),
+      Err(err) => Err(unauthorized(&format!("verify signature: {}", err))),
+    }
+  }
+}
+
+fn unauthorized(description: &str) -> Response {
+  error_response(401, "UNAUTHORIZED.GMP_GATEWAY_API", None, description)
+}
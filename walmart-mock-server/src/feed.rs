@@ -0,0 +1,148 @@
+//! `/v3/feeds`
+//!
+//! A feed counts as one item. It's `RECEIVED` after upload and progresses
+//! every time its status is returned, until `PROCESSED`.
+
+use crate::error_response;
+use crate::http::{Request, Response};
+use crate::item::push_element;
+use crate::state::{MockFeed, State};
+use chrono::Utc;
+use serde_json::Value;
+
+const DEFAULT_LIMIT: usize = 50;
+
+/// `POST /v3/feeds?feedType={type}`
+pub fn upload(state: &mut State, req: &Request) -> Response {
+  let feed_type = match req.query_value("feedType") {
+    Some(feed_type) => feed_type,
+    None => {
+      return error_response(
+        400,
+        "INVALID_REQUEST_PARAM.GMP_ITEM_INGESTOR_API",
+        Some("feedType"),
+        "feedType is required",
+      );
+    }
+  };
+
+  let feed_id = format!("{:032X}@AQYBAAA", state.next_id());
+  let now = Utc::now();
+  state.feeds.push(MockFeed {
+    feed_id: feed_id.clone(),
+    feed_type,
+    body: req.body.clone(),
+    feed_date: now.timestamp() * 1000 + i64::from(now.timestamp_subsec_millis()),
+    polls: 0,
+  });
+
+  let mut xml = String::from(
+    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><ns2:FeedAcknowledgement xmlns:ns2="http://walmart.com/">"#,
+  );
+  push_element(&mut xml, "feedId", &feed_id);
+  xml.push_str("</ns2:FeedAcknowledgement>");
+  Response::xml(200, xml)
+}
+
+/// `GET /v3/feeds`
+pub fn list(state: &mut State, req: &Request) -> Response {
+  let feed_id = req.query_value("feedId");
+  let limit = req
+    .query_value("limit")
+    .and_then(|v| v.parse().ok())
+    .unwrap_or(DEFAULT_LIMIT);
+  let offset = req
+    .query_value("offset")
+    .and_then(|v| v.parse().ok())
+    .unwrap_or(0);
+
+  let mut feeds: Vec<&mut MockFeed> = state
+    .feeds
+    .iter_mut()
+    .filter(|feed| {
+      feed_id
+        .as_ref()
+        .map(|id| feed.feed_id == *id)
+        .unwrap_or(true)
+    })
+    .collect();
+  let total = feeds.len();
+  let results: Vec<Value> = feeds
+    .iter_mut()
+    .skip(offset)
+    .take(limit)
+    .map(|feed| poll(feed))
+    .collect();
+
+  Response::json(
+    200,
+    &json!({
+      "totalResults": total,
+      "offset": offset,
+      "limit": limit,
+      "results": {
+        "feed": results
+      }
+    }),
+  )
+}
+
+/// `GET /v3/feeds/{id}`
+pub fn get(state: &mut State, feed_id: &str) -> Response {
+  let feed = match state.feeds.iter_mut().find(|feed| feed.feed_id == feed_id) {
+    Some(feed) => feed,
+    None => {
+      return error_response(
+        404,
+        "CONTENT_NOT_FOUND.GMP_ITEM_INGESTOR_API",
+        Some("feedId"),
+        &format!("feed {} was not found", feed_id),
+      );
+    }
+  };
+
+  let status = poll(feed);
+  Response::json(
+    200,
+    &json!({
+      "feedId": status["feedId"],
+      "feedStatus": status["feedStatus"],
+      "shipNode": null,
+      "ingestionErrors": {
+        "ingestionError": null
+      },
+      "itemsReceived": status["itemsReceived"],
+      "itemsSucceeded": status["itemsSucceeded"],
+      "itemsFailed": 0,
+      "itemsProcessing": status["itemsProcessing"],
+      "offset": 0,
+      "limit": DEFAULT_LIMIT,
+      "itemDetails": {
+        "itemIngestionStatus": []
+      }
+    }),
+  )
+}
+
+/// Returns the feed status and advances it
+fn poll(feed: &mut MockFeed) -> Value {
+  let status = feed.status();
+  let processed = if status == "PROCESSED" { 1 } else { 0 };
+  feed.polls += 1;
+  json!({
+    "feedId": feed.feed_id,
+    "feedType": feed.feed_type,
+    "partnerId": "10000000000",
+    "itemsReceived": 1,
+    "itemsSucceeded": processed,
+    "itemsFailed": 0,
+    "itemsProcessing": 1 - processed,
+    "feedStatus": status,
+    "feedDate": feed.feed_date,
+    "modifiedDtm": feed.feed_date,
+    "fileName": null,
+    "itemDataErrorCount": 0,
+    "itemSystemErrorCount": 0,
+    "itemTimeoutErrorCount": 0
+  })
+}
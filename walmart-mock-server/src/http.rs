@@ -0,0 +1,194 @@
+//! Just enough HTTP/1.1 to serve one request per connection
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use url::form_urlencoded;
+
+const MAX_HEADERS: usize = 64;
+
+#[derive(Debug)]
+pub struct Request {
+  pub method: String,
+  /// Path and query, e.g. `/v3/orders?limit=10`
+  pub target: String,
+  /// Names are lower case
+  pub headers: Vec<(String, String)>,
+  pub body: Vec<u8>,
+}
+
+impl Request {
+  pub fn header(&self, name: &str) -> Option<&str> {
+    let name = name.to_ascii_lowercase();
+    self
+      .headers
+      .iter()
+      .find(|(n, _)| *n == name)
+      .map(|(_, v)| v.as_str())
+  }
+
+  pub fn path(&self) -> &str {
+    self.target.split('?').next().unwrap_or("")
+  }
+
+  /// Query pairs, `?` of Walmart's `nextCursor` passed as a query string is ignored
+  pub fn query(&self) -> Vec<(String, String)> {
+    match self.target.find('?') {
+      Some(i) => form_urlencoded::parse(&self.target.as_bytes()[i + 1..])
+        .into_owned()
+        .map(|(k, v)| (k.trim_start_matches('?').to_string(), v))
+        .collect(),
+      None => vec![],
+    }
+  }
+
+  pub fn query_value(&self, name: &str) -> Option<String> {
+    self
+      .query()
+      .into_iter()
+      .find(|(k, _)| k == name)
+      .map(|(_, v)| v)
+  }
+}
+
+#[derive(Debug)]
+pub struct Response {
+  pub status: u16,
+  pub content_type: &'static str,
+  pub body: Vec<u8>,
+}
+
+impl Response {
+  pub fn json(status: u16, body: &serde_json::Value) -> Self {
+    Response {
+      status,
+      content_type: "application/json",
+      body: body.to_string().into_bytes(),
+    }
+  }
+
+  pub fn xml(status: u16, body: String) -> Self {
+    Response {
+      status,
+      content_type: "application/xml",
+      body: body.into_bytes(),
+    }
+  }
+
+  pub fn bytes(body: Vec<u8>) -> Self {
+    Response {
+      status: 200,
+      content_type: "application/octet-stream",
+      body,
+    }
+  }
+}
+
+pub fn read_request(stream: &TcpStream) -> io::Result<Request> {
+  let mut reader = BufReader::new(stream);
+
+  let mut head = vec![];
+  loop {
+    let len = reader.read_until(b'\n', &mut head)?;
+    if len == 0 {
+      return Err(invalid_data("connection closed before end of headers"));
+    }
+    if head.ends_with(b"\r\n\r\n") {
+      break;
+    }
+  }
+
+  let mut headers = [httparse::EMPTY_HEADER; MAX_HEADERS];
+  let mut req = httparse::Request::new(&mut headers);
+  match req.parse(&head) {
+    Ok(httparse::Status::Complete(_)) => {}
+    Ok(httparse::Status::Partial) => return Err(invalid_data("incomplete request head")),
+    Err(err) => return Err(invalid_data(&err.to_string())),
+  }
+
+  let mut request = Request {
+    method: req.method.unwrap_or_default().to_string(),
+    target: req.path.unwrap_or_default().to_string(),
+    headers: req
+      .headers
+      .iter()
+      .map(|h| {
+        (
+          h.name.to_ascii_lowercase(),
+          String::from_utf8_lossy(h.value).into_owned(),
+        )
+      })
+      .collect(),
+    body: vec![],
+  };
+
+  if request
+    .header("transfer-encoding")
+    .map(|v| v.eq_ignore_ascii_case("chunked"))
+    .unwrap_or(false)
+  {
+    request.body = read_chunked(&mut reader)?;
+  } else if let Some(len) = request.header("content-length") {
+    let len: usize = len
+      .trim()
+      .parse()
+      .map_err(|_| invalid_data("invalid content-length"))?;
+    let mut body = vec![0; len];
+    reader.read_exact(&mut body)?;
+    request.body = body;
+  }
+
+  Ok(request)
+}
+
+fn read_chunked<R: BufRead>(reader: &mut R) -> io::Result<Vec<u8>> {
+  let mut body = vec![];
+  loop {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let size = line.trim().split(';').next().unwrap_or("");
+    let size = usize::from_str_radix(size, 16).map_err(|_| invalid_data("invalid chunk size"))?;
+    if size == 0 {
+      // trailers
+      loop {
+        line.clear();
+        reader.read_line(&mut line)?;
+        if line.trim().is_empty() {
+          return Ok(body);
+        }
+      }
+    }
+    let start = body.len();
+    body.resize(start + size, 0);
+    reader.read_exact(&mut body[start..])?;
+    line.clear();
+    reader.read_line(&mut line)?;
+  }
+}
+
+pub fn write_response(mut stream: &TcpStream, res: &Response) -> io::Result<()> {
+  write!(
+    stream,
+    "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+    res.status,
+    get_reason(res.status),
+    res.content_type,
+    res.body.len()
+  )?;
+  stream.write_all(&res.body)?;
+  stream.flush()
+}
+
+fn get_reason(status: u16) -> &'static str {
+  match status {
+    200 => "OK",
+    400 => "Bad Request",
+    401 => "Unauthorized",
+    404 => "Not Found",
+    405 => "Method Not Allowed",
+    _ => "Unknown",
+  }
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+  io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
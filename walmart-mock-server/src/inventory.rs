@@ -0,0 +1,74 @@
+//! `/v3/inventory`
+
+use crate::error_response;
+use crate::http::{Request, Response};
+use crate::state::{MockInventory, State};
+use serde_json::Value;
+
+/// `GET /v3/inventory?sku={sku}`
+pub fn get(state: &State, req: &Request) -> Response {
+  let sku = req.query_value("sku").unwrap_or_default();
+  match state.inventory.get(&sku) {
+    Some(inventory) => Response::json(200, &to_json(&sku, inventory)),
+    None => not_found(&sku),
+  }
+}
+
+/// `PUT /v3/inventory?sku={sku}`, the item must exist
+pub fn update(state: &mut State, req: &Request) -> Response {
+  let sku = req.query_value("sku").unwrap_or_default();
+  if !state.items.iter().any(|item| item.sku == sku) {
+    return not_found(&sku);
+  }
+
+  let body: Value = match serde_json::from_slice(&req.body) {
+    Ok(body) => body,
+    Err(err) => {
+      return error_response(
+        400,
+        "INVALID_REQUEST_CONTENT.GMP_INVENTORY_API",
+        None,
+        &format!("invalid json: {}", err),
+      );
+    }
+  };
+  let amount = match body["quantity"]["amount"].as_i64() {
+    Some(amount) if amount >= 0 => amount as i32,
+    _ => {
+      return error_response(
+        400,
+        "INVALID_REQUEST_CONTENT.GMP_INVENTORY_API",
+        Some("quantity"),
+        "quantity.amount must be a non-negative integer",
+      );
+    }
+  };
+
+  let inventory = MockInventory {
+    amount,
+    fulfillment_lag_time: body["fulfillmentLagTime"].as_i64().map(|v| v as i32),
+  };
+  let res = to_json(&sku, &inventory);
+  state.inventory.insert(sku, inventory);
+  Response::json(200, &res)
+}
+
+fn to_json(sku: &str, inventory: &MockInventory) -> Value {
+  json!({
+    "sku": sku,
+    "quantity": {
+      "unit": "EACH",
+      "amount": inventory.amount
+    },
+    "fulfillmentLagTime": inventory.fulfillment_lag_time
+  })
+}
+
+fn not_found(sku: &str) -> Response {
+  error_response(
+    404,
+    "CONTENT_NOT_FOUND.GMP_INVENTORY_API",
+    Some("sku"),
+    &format!("sku {} was not found", sku),
+  )
+}
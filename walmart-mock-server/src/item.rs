@@ -0,0 +1,98 @@
+//! `/v3/items`
+
+use crate::http::{Request, Response};
+use crate::state::{MockItem, State};
+
+const DEFAULT_LIMIT: usize = 20;
+
+/// `GET /v3/items`, paged by `nextCursor` (US) or `offset` (Canada)
+pub fn list(state: &State, req: &Request) -> Response {
+  let sku = req.query_value("sku");
+  let items: Vec<&MockItem> = state
+    .items
+    .iter()
+    .filter(|item| sku.as_ref().map(|sku| item.sku == *sku).unwrap_or(true))
+    .collect();
+
+  if items.is_empty() && sku.is_some() {
+    return Response::xml(
+      404,
+      get_error_xml(
+        "CONTENT_NOT_FOUND.GMP_ITEM_QUERY_API",
+        "sku",
+        "Item not found",
+      ),
+    );
+  }
+
+  let limit = req
+    .query_value("limit")
+    .and_then(|v| v.parse().ok())
+    .unwrap_or(DEFAULT_LIMIT);
+  // the cursor is the offset of the next page, `*` is the first page
+  let offset: usize = req
+    .query_value("nextCursor")
+    .filter(|cursor| cursor != "*")
+    .or_else(|| req.query_value("offset"))
+    .and_then(|v| v.parse().ok())
+    .unwrap_or(0);
+
+  let page: Vec<&MockItem> = items.iter().skip(offset).take(limit).cloned().collect();
+  let next_offset = offset + page.len();
+
+  let mut xml = String::from(
+    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><ns2:ItemResponses xmlns:ns2="http://walmart.com/">"#,
+  );
+  for item in page {
+    xml.push_str("<ns2:ItemResponse>");
+    push_element(&mut xml, "mart", "WALMART_US");
+    push_element(&mut xml, "sku", &item.sku);
+    push_element(&mut xml, "wpid", &item.wpid);
+    push_element(&mut xml, "upc", &item.upc);
+    push_element(&mut xml, "gtin", &item.gtin);
+    push_element(&mut xml, "productName", &item.product_name);
+    push_element(&mut xml, "shelf", "[]");
+    push_element(&mut xml, "productType", &item.product_type);
+    xml.push_str("<ns2:price>");
+    push_element(&mut xml, "currency", &item.currency);
+    push_element(&mut xml, "amount", &item.price);
+    xml.push_str("</ns2:price>");
+    push_element(&mut xml, "publishedStatus", &item.published_status);
+    xml.push_str("</ns2:ItemResponse>");
+  }
+  push_element(&mut xml, "totalItems", &items.len().to_string());
+  if next_offset < items.len() {
+    push_element(&mut xml, "nextCursor", &next_offset.to_string());
+  }
+  xml.push_str("</ns2:ItemResponses>");
+
+  Response::xml(200, xml)
+}
+
+fn get_error_xml(code: &str, field: &str, description: &str) -> String {
+  let mut xml = String::from(
+    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><ns2:errors xmlns:ns2="http://walmart.com/"><ns2:error>"#,
+  );
+  push_element(&mut xml, "code", code);
+  push_element(&mut xml, "field", field);
+  push_element(&mut xml, "description", description);
+  push_element(&mut xml, "info", description);
+  push_element(&mut xml, "severity", "ERROR");
+  push_element(&mut xml, "category", "APPLICATION");
+  xml.push_str("</ns2:error></ns2:errors>");
+  xml
+}
+
+pub(crate) fn push_element(xml: &mut String, name: &str, text: &str) {
+  xml.push_str(&format!("<ns2:{}>", name));
+  for c in text.chars() {
+    match c {
+      '<' => xml.push_str("&lt;"),
+      '>' => xml.push_str("&gt;"),
+      '&' => xml.push_str("&amp;"),
+      '"' => xml.push_str("&quot;"),
+      _ => xml.push(c),
+    }
+  }
+  xml.push_str(&format!("</ns2:{}>", name));
+}
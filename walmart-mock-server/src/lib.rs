@@ -0,0 +1,319 @@
+//! In-process mock of the Walmart Marketplace APIs used by `walmart_partner_api`
+//!
+//! The server listens on a random local port, point a client to it with
+//! `ClientBuilder::base_url(&server.url())`. It keeps state between requests:
+//! acknowledged orders become `Acknowledged`, shipped lines get tracking info
+//! and uploaded feeds progress to `PROCESSED` as their status is polled.
+//!
+//! Requests are authenticated like Walmart does, with a bearer token from `/v3/token`
+//! or a `WM_SEC.AUTH_SIGNATURE` verified with `Signature`.
+
+#[macro_use]
+extern crate log;
+#[macro_use]
+extern crate serde_json;
+
+mod auth;
+mod feed;
+mod http;
+mod inventory;
+mod item;
+mod order;
+mod report;
+mod returns;
+mod state;
+
+pub use self::order::sample_order;
+pub use self::returns::sample_return;
+pub use self::state::{MockFeed, MockInventory, MockItem, FEED_POLLS_TO_PROCESS};
+
+use self::auth::Auth;
+use self::http::{Request, Response};
+use self::state::State;
+use serde_json::Value;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use walmart_partner_api::result::WalmartResult;
+use walmart_partner_api::Signature;
+
+/// Builder for `MockServer`
+#[derive(Default)]
+pub struct MockServerBuilder {
+  token_api: Option<(String, String)>,
+  signature: Option<(String, String)>,
+}
+
+impl MockServerBuilder {
+  /// Accepts Token API credentials
+  pub fn token_api(mut self, client_id: &str, client_secret: &str) -> Self {
+    self.token_api = Some((client_id.to_string(), client_secret.to_string()));
+    self
+  }
+
+  /// Accepts signatures made with the private key, in the format of `WalmartCredential::Signature`
+  pub fn signature(mut self, consumer_id: &str, private_key: &str) -> Self {
+    self.signature = Some((consumer_id.to_string(), private_key.to_string()));
+    self
+  }
+
+  pub fn start(self) -> WalmartResult<MockServer> {
+    let signature = match self.signature {
+      Some((consumer_id, private_key)) => Some(Signature::new(&consumer_id, &private_key)?),
+      None => None,
+    };
+
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let addr = listener.local_addr()?;
+    let inner = Arc::new(Inner {
+      base_url: format!("http://{}", addr),
+      auth: Auth::new(self.token_api, signature),
+      state: Mutex::new(State::default()),
+      shutdown: AtomicBool::new(false),
+    });
+
+    let handle = {
+      let inner = inner.clone();
+      thread::spawn(move || {
+        for stream in listener.incoming() {
+          if inner.shutdown.load(Ordering::SeqCst) {
+            break;
+          }
+          match stream {
+            Ok(stream) => inner.serve(stream),
+            Err(err) => warn!("mock server: accept: {}", err),
+          }
+        }
+      })
+    };
+
+    Ok(MockServer {
+      addr,
+      inner,
+      handle: Some(handle),
+    })
+  }
+}
+
+struct Inner {
+  base_url: String,
+  auth: Auth,
+  state: Mutex<State>,
+  shutdown: AtomicBool,
+}
+
+impl Inner {
+  fn serve(&self, stream: TcpStream) {
+    let req = match http::read_request(&stream) {
+      Ok(req) => req,
+      Err(err) => {
+        warn!("mock server: read request: {}", err);
+        return;
+      }
+    };
+    let res = self.handle(&req);
+    debug!(
+      "mock server: {} {} -> {}",
+      req.method, req.target, res.status
+    );
+    if let Err(err) = http::write_response(&stream, &res) {
+      warn!("mock server: write response: {}", err);
+    }
+  }
+
+  fn handle(&self, req: &Request) -> Response {
+    let mut state = self.state.lock().unwrap();
+
+    // `/v3/ca/orders` is routed like `/v3/orders`
+    let segments: Vec<&str> = req
+      .path()
+      .split('/')
+      .filter(|seg| !seg.is_empty())
+      .enumerate()
+      .filter(|&(i, seg)| !(i == 1 && seg == "ca"))
+      .map(|(_, seg)| seg)
+      .collect();
+
+    if let ["v3", "token"] = segments.as_slice() {
+      return match req.method.as_str() {
+        "POST" => self.auth.issue_token(&mut state, req),
+        _ => method_not_allowed(),
+      };
+    }
+
+    if let Err(res) = self.auth.check(&state, &self.base_url, req) {
+      return res;
+    }
+
+    match (req.method.as_str(), segments.as_slice()) {
+      ("GET", ["v3", "orders"]) => order::list(&state, req, false),
+      ("GET", ["v3", "orders", "released"]) => order::list(&state, req, true),
+      ("GET", ["v3", "orders", id]) => order::get(&state, id),
+      ("POST", ["v3", "orders", id, "acknowledge"]) => order::ack(&mut state, id),
+      ("POST", ["v3", "orders", id, "shipping"]) => order::ship(&mut state, id, req),
+      ("POST", ["v3", "orders", id, "cancel"]) => order::cancel(&mut state, id, req),
+      ("POST", ["v3", "orders", id, "refund"]) => order::refund(&mut state, id, req),
+      ("GET", ["v3", "returns"]) => returns::list(&state, req),
+      ("POST", ["v3", "returns", id, "refund"]) => returns::refund(&mut state, id, req),
+      ("GET", ["v3", "items"]) => item::list(&state, req),
+      ("GET", ["v3", "inventory"]) => inventory::get(&state, req),
+      ("PUT", ["v3", "inventory"]) => inventory::update(&mut state, req),
+      ("GET", ["v3", "feeds"]) => feed::list(&mut state, req),
+      ("POST", ["v3", "feeds"]) => feed::upload(&mut state, req),
+      ("GET", ["v3", "feeds", id]) => feed::get(&mut state, id),
+      ("GET", ["v2", "getReport"]) => report::get(&state, req),
+      _ => error_response(
+        404,
+        "NOT_FOUND.GMP_GATEWAY_API",
+        None,
+        &format!("no mock for {} {}", req.method, req.path()),
+      ),
+    }
+  }
+}
+
+/// A running mock server, stopped on drop
+pub struct MockServer {
+  addr: SocketAddr,
+  inner: Arc<Inner>,
+  handle: Option<JoinHandle<()>>,
+}
+
+impl MockServer {
+  pub fn builder() -> MockServerBuilder {
+    MockServerBuilder::default()
+  }
+
+  /// Base url for `ClientBuilder::base_url`
+  pub fn url(&self) -> String {
+    self.inner.base_url.clone()
+  }
+
+  /// Adds or replaces an order, in the JSON format of `GET /v3/orders/{id}`
+  pub fn add_order(&self, order: Value) {
+    let id = order["purchaseOrderId"]
+      .as_str()
+      .expect("purchaseOrderId")
+      .to_string();
+    self.inner.state.lock().unwrap().orders.insert(id, order);
+  }
+
+  pub fn get_order(&self, purchase_order_id: &str) -> Option<Value> {
+    self
+      .inner
+      .state
+      .lock()
+      .unwrap()
+      .orders
+      .get(purchase_order_id)
+      .cloned()
+  }
+
+  /// Adds or replaces a return order, in the JSON format of `GET /v3/returns`
+  pub fn add_return(&self, order: Value) {
+    let id = order["returnOrderId"]
+      .as_str()
+      .expect("returnOrderId")
+      .to_string();
+    self.inner.state.lock().unwrap().returns.insert(id, order);
+  }
+
+  pub fn get_return(&self, return_order_id: &str) -> Option<Value> {
+    self
+      .inner
+      .state
+      .lock()
+      .unwrap()
+      .returns
+      .get(return_order_id)
+      .cloned()
+  }
+
+  pub fn add_item(&self, item: MockItem) {
+    self.inner.state.lock().unwrap().items.push(item);
+  }
+
+  pub fn set_inventory(&self, sku: &str, inventory: MockInventory) {
+    self
+      .inner
+      .state
+      .lock()
+      .unwrap()
+      .inventory
+      .insert(sku.to_string(), inventory);
+  }
+
+  pub fn get_inventory(&self, sku: &str) -> Option<MockInventory> {
+    self.inner.state.lock().unwrap().inventory.get(sku).cloned()
+  }
+
+  pub fn get_feeds(&self) -> Vec<MockFeed> {
+    self.inner.state.lock().unwrap().feeds.clone()
+  }
+
+  /// Serves `body` for `GET /v2/getReport?type={report_type}`.
+  /// The `item` report is generated from the items if not set.
+  pub fn set_report(&self, report_type: &str, body: Vec<u8>) {
+    self
+      .inner
+      .state
+      .lock()
+      .unwrap()
+      .reports
+      .insert(report_type.to_string(), body);
+  }
+
+  /// Invalidates all issued access tokens, the next request using one gets a `401`
+  pub fn expire_tokens(&self) {
+    self.inner.state.lock().unwrap().tokens.clear();
+  }
+}
+
+impl Drop for MockServer {
+  fn drop(&mut self) {
+    self.inner.shutdown.store(true, Ordering::SeqCst);
+    // wake up the accept loop
+    let _ = TcpStream::connect(self.addr);
+    if let Some(handle) = self.handle.take() {
+      let _ = handle.join();
+    }
+  }
+}
+
+/// A Walmart JSON error response
+pub(crate) fn error_response(
+  status: u16,
+  code: &str,
+  field: Option<&str>,
+  description: &str,
+) -> Response {
+  Response::json(
+    status,
+    &json!({
+      "errors": {
+        "error": [
+          {
+            "code": code,
+            "field": field,
+            "description": description,
+            "info": description,
+            "severity": "ERROR",
+            "category": "DATA",
+            "causes": [],
+            "errorIdentifiers": {}
+          }
+        ]
+      }
+    }),
+  )
+}
+
+fn method_not_allowed() -> Response {
+  error_response(
+    405,
+    "METHOD_NOT_ALLOWED.GMP_GATEWAY_API",
+    None,
+    "method not allowed",
+  )
+}
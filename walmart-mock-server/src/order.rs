@@ -0,0 +1,542 @@
+//! `/v3/orders`
+
+use crate::error_response;
+use crate::http::{Request, Response};
+use crate::state::State;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+const DEFAULT_LIMIT: usize = 10;
+const ORDER_ERROR: &str = "INVALID_REQUEST_CONTENT.GMP_ORDER_API";
+
+/// A US order with one `Created` line of quantity 1 per sku
+pub fn sample_order(purchase_order_id: &str, skus: &[&str]) -> Value {
+  let now = now_ms();
+  let lines: Vec<Value> = skus
+    .iter()
+    .enumerate()
+    .map(|(i, sku)| {
+      json!({
+        "lineNumber": (i + 1).to_string(),
+        "item": {
+          "productName": format!("Product {}", sku),
+          "sku": sku
+        },
+        "charges": {
+          "charge": [
+            {
+              "chargeType": "PRODUCT",
+              "chargeName": "ItemPrice",
+              "chargeAmount": {
+                "currency": "USD",
+                "amount": 10.0
+              },
+              "tax": null
+            }
+          ]
+        },
+        "orderLineQuantity": {
+          "unitOfMeasurement": "EACH",
+          "amount": "1"
+        },
+        "statusDate": now,
+        "orderLineStatuses": {
+          "orderLineStatus": [
+            {
+              "status": "Created",
+              "statusQuantity": {
+                "unitOfMeasurement": "EACH",
+                "amount": "1"
+              },
+              "cancellationReason": null,
+              "trackingInfo": null
+            }
+          ]
+        },
+        "refund": null
+      })
+    })
+    .collect();
+
+  json!({
+    "purchaseOrderId": purchase_order_id,
+    "customerOrderId": format!("C{}", purchase_order_id),
+    "customerEmailId": "customer@relay.walmart.com",
+    "orderDate": now,
+    "shippingInfo": {
+      "phone": "2025550100",
+      "estimatedDeliveryDate": now + 5 * 86_400_000,
+      "estimatedShipDate": now + 86_400_000,
+      "methodCode": "Standard",
+      "postalAddress": {
+        "name": "Jane Doe",
+        "address1": "702 SW 8th St",
+        "address2": null,
+        "city": "Bentonville",
+        "state": "AR",
+        "postalCode": "72716",
+        "country": "USA",
+        "addressType": "RESIDENTIAL"
+      }
+    },
+    "orderLines": {
+      "orderLine": lines
+    }
+  })
+}
+
+/// `GET /v3/orders` and `GET /v3/orders/released`, released orders have only `Created` lines
+pub fn list(state: &State, req: &Request, released: bool) -> Response {
+  let status = req.query_value("status");
+  let purchase_order_id = req.query_value("purchaseOrderId");
+  let customer_order_id = req.query_value("customerOrderId");
+  let sku = req.query_value("sku");
+  // like Walmart US, only seller fulfilled orders unless another node type is requested
+  let ship_node_type = req
+    .query_value("shipNodeType")
+    .unwrap_or_else(|| "SellerFulfilled".to_string());
+  let created = get_date_range(req, "createdStartDate", "createdEndDate");
+  let modified = get_date_range(req, "lastModifiedStartDate", "lastModifiedEndDate");
+
+  let orders: Vec<&Value> = state
+    .orders
+    .values()
+    .filter(|order| {
+      let statuses: Vec<&str> = get_lines(order)
+        .into_iter()
+        .filter_map(get_line_status)
+        .collect();
+      if released && statuses.iter().any(|s| *s != "Created") {
+        return false;
+      }
+      if let Some(ref status) = status {
+        if !statuses.contains(&status.as_str()) {
+          return false;
+        }
+      }
+      if let Some(ref id) = purchase_order_id {
+        if order["purchaseOrderId"].as_str() != Some(id) {
+          return false;
+        }
+      }
+      if let Some(ref id) = customer_order_id {
+        if order["customerOrderId"].as_str() != Some(id) {
+          return false;
+        }
+      }
+      if let Some(ref sku) = sku {
+        if !get_lines(order)
+          .into_iter()
+          .any(|line| line["item"]["sku"].as_str() == Some(sku))
+        {
+          return false;
+        }
+      }
+      if order["shipNode"]["type"]
+        .as_str()
+        .unwrap_or("SellerFulfilled")
+        != ship_node_type
+      {
+        return false;
+      }
+      let order_date = order["orderDate"].as_i64().unwrap_or_default();
+      if !in_range(created, order_date) {
+        return false;
+      }
+      let last_modified = get_lines(order)
+        .into_iter()
+        .filter_map(|line| line["statusDate"].as_i64())
+        .fold(order_date, i64::max);
+      if !in_range(modified, last_modified) {
+        return false;
+      }
+      true
+    })
+    .collect();
+
+  let limit = req
+    .query_value("limit")
+    .and_then(|v| v.parse().ok())
+    .unwrap_or(DEFAULT_LIMIT);
+  let offset: usize = req
+    .query_value("soIndex")
+    .and_then(|v| v.parse().ok())
+    .unwrap_or(0);
+  let page: Vec<&Value> = orders.iter().skip(offset).take(limit).cloned().collect();
+
+  // like Walmart, the cursor is a query string which keeps the filters
+  let next_offset = offset + page.len();
+  let next_cursor = if next_offset < orders.len() {
+    let mut cursor = format!(
+      "?limit={}&hasMoreElements=true&soIndex={}",
+      limit, next_offset
+    );
+    for (k, v) in req.query() {
+      if k != "limit" && k != "soIndex" && k != "hasMoreElements" && k != "nextCursor" {
+        cursor.push_str(&format!("&{}={}", k, v));
+      }
+    }
+    Some(cursor)
+  } else {
+    None
+  };
+
+  Response::json(
+    200,
+    &json!({
+      "list": {
+        "meta": {
+          "totalCount": orders.len(),
+          "limit": limit,
+          "nextCursor": next_cursor
+        },
+        "elements": {
+          "order": page
+        }
+      }
+    }),
+  )
+}
+
+/// `GET /v3/orders/{id}`
+pub fn get(state: &State, purchase_order_id: &str) -> Response {
+  match state.orders.get(purchase_order_id) {
+    Some(order) => Response::json(200, &json!({ "order": order })),
+    None => not_found(purchase_order_id),
+  }
+}
+
+/// `POST /v3/orders/{id}/acknowledge`
+pub fn ack(state: &mut State, purchase_order_id: &str) -> Response {
+  let order = match state.orders.get_mut(purchase_order_id) {
+    Some(order) => order,
+    None => return not_found(purchase_order_id),
+  };
+
+  let now = now_ms();
+  for line in get_lines_mut(order) {
+    if get_line_status(line) == Some("Created") {
+      line["orderLineStatuses"]["orderLineStatus"][0]["status"] = json!("Acknowledged");
+      line["statusDate"] = json!(now);
+    }
+  }
+  Response::json(200, &json!({ "order": order }))
+}
+
+/// `POST /v3/orders/{id}/shipping`, only acknowledged units can be shipped
+///
+/// A line can be shipped in several packages, units that are not shipped
+/// stay acknowledged.
+pub fn ship(state: &mut State, purchase_order_id: &str, req: &Request) -> Response {
+  let order = match state.orders.get_mut(purchase_order_id) {
+    Some(order) => order,
+    None => return not_found(purchase_order_id),
+  };
+
+  let body: Value = match serde_json::from_slice(&req.body) {
+    Ok(body) => body,
+    Err(err) => return bad_request(None, &format!("invalid json: {}", err)),
+  };
+  let shipped_lines = match body["orderShipment"]["orderLines"]["orderLine"].as_array() {
+    Some(lines) => lines.clone(),
+    None => return bad_request(Some("orderLines"), "orderLines is required"),
+  };
+
+  // validate every line before changing any
+  let mut shipped_amounts: Vec<(String, u64)> = vec![];
+  for shipped in &shipped_lines {
+    let line_number = shipped["lineNumber"].as_str().unwrap_or_default();
+    if !get_lines(order)
+      .into_iter()
+      .any(|line| line["lineNumber"].as_str() == Some(line_number))
+    {
+      return bad_request(
+        Some("lineNumber"),
+        &format!("line {} was not found", line_number),
+      );
+    }
+    let statuses = get_statuses(shipped);
+    if statuses.is_empty() || statuses.iter().any(|s| s["trackingInfo"].is_null()) {
+      return bad_request(Some("trackingInfo"), "trackingInfo is required");
+    }
+    let amount: u64 = statuses.iter().map(|s| get_amount(s)).sum();
+    match shipped_amounts.iter_mut().find(|(n, _)| n == line_number) {
+      Some(entry) => entry.1 += amount,
+      None => shipped_amounts.push((line_number.to_string(), amount)),
+    }
+  }
+  for (line_number, amount) in &shipped_amounts {
+    let line = get_lines(order)
+      .into_iter()
+      .find(|line| line["lineNumber"].as_str() == Some(line_number))
+      .unwrap();
+    let acknowledged = get_status_amount(line, "Acknowledged");
+    if *amount == 0 || *amount > acknowledged {
+      return bad_request(
+        Some("lineNumber"),
+        &format!(
+          "line {} can not ship {} units, {} are acknowledged",
+          line_number, amount, acknowledged
+        ),
+      );
+    }
+  }
+
+  let now = now_ms();
+  for (line_number, amount) in shipped_amounts {
+    let mut new_statuses: Vec<Value> = shipped_lines
+      .iter()
+      .filter(|shipped| shipped["lineNumber"].as_str() == Some(&line_number))
+      .flat_map(|shipped| get_statuses(shipped).into_iter().cloned())
+      .map(|mut status| {
+        status["cancellationReason"] = Value::Null;
+        status
+      })
+      .collect();
+    if let Some(line) = get_lines_mut(order)
+      .into_iter()
+      .find(|line| line["lineNumber"].as_str() == Some(&line_number))
+    {
+      let remaining = get_status_amount(line, "Acknowledged") - amount;
+      let mut statuses: Vec<Value> = get_statuses(line)
+        .into_iter()
+        .filter(|s| s["status"] != "Acknowledged")
+        .cloned()
+        .collect();
+      statuses.append(&mut new_statuses);
+      if remaining > 0 {
+        statuses.push(json!({
+          "status": "Acknowledged",
+          "statusQuantity": {
+            "unitOfMeasurement": "EACH",
+            "amount": remaining.to_string()
+          },
+          "cancellationReason": null,
+          "trackingInfo": null
+        }));
+      }
+      line["orderLineStatuses"]["orderLineStatus"] = json!(statuses);
+      line["statusDate"] = json!(now);
+    }
+  }
+  Response::json(200, &json!({ "order": order }))
+}
+
+/// `POST /v3/orders/{id}/cancel`, shipped lines can't be cancelled
+pub fn cancel(state: &mut State, purchase_order_id: &str, req: &Request) -> Response {
+  let order = match state.orders.get_mut(purchase_order_id) {
+    Some(order) => order,
+    None => return not_found(purchase_order_id),
+  };
+
+  let body: Value = match serde_json::from_slice(&req.body) {
+    Ok(body) => body,
+    Err(err) => return bad_request(None, &format!("invalid json: {}", err)),
+  };
+  let cancelled_lines = match body["orderCancellation"]["orderLines"]["orderLine"].as_array() {
+    Some(lines) => lines.clone(),
+    None => return bad_request(Some("orderLines"), "orderLines is required"),
+  };
+
+  // validate every line before changing any
+  for cancelled in &cancelled_lines {
+    let line_number = cancelled["lineNumber"].as_str().unwrap_or_default();
+    let line = match get_lines(order)
+      .into_iter()
+      .find(|line| line["lineNumber"].as_str() == Some(line_number))
+    {
+      Some(line) => line,
+      None => {
+        return bad_request(
+          Some("lineNumber"),
+          &format!("line {} was not found", line_number),
+        );
+      }
+    };
+    match get_line_status(line) {
+      Some("Created") | Some("Acknowledged") => {}
+      status => {
+        return bad_request(
+          Some("lineNumber"),
+          &format!(
+            "line {} can not be cancelled in status {}",
+            line_number,
+            status.unwrap_or_default()
+          ),
+        );
+      }
+    }
+    let statuses = &cancelled["orderLineStatuses"]["orderLineStatus"];
+    if statuses[0]["cancellationReason"].as_str().is_none() {
+      return bad_request(Some("cancellationReason"), "cancellationReason is required");
+    }
+  }
+
+  let now = now_ms();
+  for cancelled in cancelled_lines {
+    let line_number = cancelled["lineNumber"]
+      .as_str()
+      .unwrap_or_default()
+      .to_string();
+    if let Some(line) = get_lines_mut(order)
+      .into_iter()
+      .find(|line| line["lineNumber"].as_str() == Some(&line_number))
+    {
+      let mut statuses = cancelled["orderLineStatuses"]["orderLineStatus"].clone();
+      if let Some(statuses) = statuses.as_array_mut() {
+        for status in statuses {
+          status["trackingInfo"] = Value::Null;
+        }
+      }
+      line["orderLineStatuses"]["orderLineStatus"] = statuses;
+      line["statusDate"] = json!(now);
+    }
+  }
+  Response::json(200, &json!({ "order": order }))
+}
+
+/// `POST /v3/orders/{id}/refund`, only shipped lines can be refunded
+pub fn refund(state: &mut State, purchase_order_id: &str, req: &Request) -> Response {
+  let refund_id = state.next_id();
+  let order = match state.orders.get_mut(purchase_order_id) {
+    Some(order) => order,
+    None => return not_found(purchase_order_id),
+  };
+
+  let body: Value = match serde_json::from_slice(&req.body) {
+    Ok(body) => body,
+    Err(err) => return bad_request(None, &format!("invalid json: {}", err)),
+  };
+  let refunded_lines = match body["orderRefund"]["orderLines"]["orderLine"].as_array() {
+    Some(lines) => lines.clone(),
+    None => return bad_request(Some("orderLines"), "orderLines is required"),
+  };
+
+  // validate every line before changing any
+  for refunded in &refunded_lines {
+    let line_number = refunded["lineNumber"].as_str().unwrap_or_default();
+    let line = match get_lines(order)
+      .into_iter()
+      .find(|line| line["lineNumber"].as_str() == Some(line_number))
+    {
+      Some(line) => line,
+      None => {
+        return bad_request(
+          Some("lineNumber"),
+          &format!("line {} was not found", line_number),
+        );
+      }
+    };
+    if get_line_status(line) != Some("Shipped") {
+      return bad_request(
+        Some("lineNumber"),
+        &format!("line {} is not shipped", line_number),
+      );
+    }
+    let charges = &refunded["refunds"]["refund"][0]["refundCharges"]["refundCharge"];
+    let is_negative = charges.as_array().map_or(false, |charges| {
+      !charges.is_empty()
+        && charges.iter().all(|charge| {
+          charge["charge"]["chargeAmount"]["amount"]
+            .as_f64()
+            .map_or(false, |amount| amount < 0.0)
+        })
+    });
+    if !is_negative {
+      return bad_request(
+        Some("chargeAmount"),
+        "refund charge amounts must be negative",
+      );
+    }
+  }
+
+  for refunded in refunded_lines {
+    let line_number = refunded["lineNumber"]
+      .as_str()
+      .unwrap_or_default()
+      .to_string();
+    if let Some(line) = get_lines_mut(order)
+      .into_iter()
+      .find(|line| line["lineNumber"].as_str() == Some(&line_number))
+    {
+      let mut refund = refunded["refunds"]["refund"][0].clone();
+      refund["refundId"] = json!(refund_id.to_string());
+      line["refund"] = refund;
+    }
+  }
+  Response::json(200, &json!({ "order": order }))
+}
+
+fn get_lines(order: &Value) -> Vec<&Value> {
+  order["orderLines"]["orderLine"]
+    .as_array()
+    .map(|lines| lines.iter().collect())
+    .unwrap_or_default()
+}
+
+fn get_lines_mut(order: &mut Value) -> Vec<&mut Value> {
+  order["orderLines"]["orderLine"]
+    .as_array_mut()
+    .map(|lines| lines.iter_mut().collect())
+    .unwrap_or_default()
+}
+
+fn get_statuses(line: &Value) -> Vec<&Value> {
+  line["orderLineStatuses"]["orderLineStatus"]
+    .as_array()
+    .map(|statuses| statuses.iter().collect())
+    .unwrap_or_default()
+}
+
+fn get_amount(status: &Value) -> u64 {
+  status["statusQuantity"]["amount"]
+    .as_str()
+    .and_then(|amount| amount.parse().ok())
+    .unwrap_or_default()
+}
+
+/// Units of a line in `status`
+fn get_status_amount(line: &Value, status: &str) -> u64 {
+  get_statuses(line)
+    .into_iter()
+    .filter(|s| s["status"] == status)
+    .map(get_amount)
+    .sum()
+}
+
+fn get_line_status(line: &Value) -> Option<&str> {
+  line["orderLineStatuses"]["orderLineStatus"][0]["status"].as_str()
+}
+
+/// Inclusive range in milliseconds of a date range query, order dates have no sub-millisecond part
+fn get_date_range(req: &Request, start: &str, end: &str) -> (Option<i64>, Option<i64>) {
+  let parse = |name| {
+    req
+      .query_value(name)
+      .and_then(|v| DateTime::parse_from_rfc3339(&v).ok())
+      .map(|v| v.timestamp_millis())
+  };
+  (parse(start), parse(end))
+}
+
+fn in_range(range: (Option<i64>, Option<i64>), value: i64) -> bool {
+  range.0.map_or(true, |start| value >= start) && range.1.map_or(true, |end| value <= end)
+}
+
+fn now_ms() -> i64 {
+  let now = Utc::now();
+  now.timestamp() * 1000 + i64::from(now.timestamp_subsec_millis())
+}
+
+fn not_found(purchase_order_id: &str) -> Response {
+  error_response(
+    404,
+    "CONTENT_NOT_FOUND.GMP_ORDER_API",
+    Some("purchaseOrderId"),
+    &format!("order {} was not found", purchase_order_id),
+  )
+}
+
+fn bad_request(field: Option<&str>, description: &str) -> Response {
+  error_response(400, ORDER_ERROR, field, description)
+}
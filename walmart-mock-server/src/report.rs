@@ -0,0 +1,123 @@
+//! `/v2/getReport`
+
+use crate::error_response;
+use crate::http::{Request, Response};
+use crate::state::State;
+use std::io::{Cursor, Write};
+use zip::write::{FileOptions, ZipWriter};
+
+const ITEM_REPORT_HEADERS: &[&str] = &[
+  "PARTNER ID",
+  "SKU",
+  "PRODUCT NAME",
+  "PRODUCT CATEGORY",
+  "PRICE",
+  "CURRENCY",
+  "PUBLISH STATUS",
+  "STATUS CHANGE REASON",
+  "LIFECYCLE STATUS",
+  "INVENTORY COUNT",
+  "SHIP METHODS",
+  "WPID",
+  "ITEM ID",
+  "GTIN",
+  "UPC",
+  "PRIMARY IMAGE URL",
+  "SHELF NAME",
+  "PRIMARY CAT PATH",
+  "OFFER START DATE",
+  "OFFER END DATE",
+  "ITEM CREATION DATE",
+  "ITEM LAST UPDATED",
+  "ITEM PAGE URL",
+  "REVIEWS COUNT",
+  "AVERAGE RATING",
+  "SEARCHABLE?",
+];
+
+/// `GET /v2/getReport?type={type}`, a zipped CSV file
+pub fn get(state: &State, req: &Request) -> Response {
+  let report_type = req.query_value("type").unwrap_or_default();
+  if let Some(body) = state.reports.get(&report_type) {
+    return Response::bytes(body.clone());
+  }
+
+  if report_type != "item" {
+    return error_response(
+      400,
+      "INVALID_REQUEST_PARAM.GMP_REPORTS_API",
+      Some("type"),
+      &format!("report type {} is not supported", report_type),
+    );
+  }
+
+  match get_item_report(state) {
+    Ok(body) => Response::bytes(body),
+    Err(err) => error_response(
+      500,
+      "SYSTEM_ERROR.GMP_REPORTS_API",
+      None,
+      &format!("generate report: {}", err),
+    ),
+  }
+}
+
+fn get_item_report(state: &State) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+  let mut csv = get_csv_line(ITEM_REPORT_HEADERS.iter().map(|h| h.to_string()));
+  for (i, item) in state.items.iter().enumerate() {
+    let inventory = state
+      .inventory
+      .get(&item.sku)
+      .map(|inventory| inventory.amount.to_string())
+      .unwrap_or_default();
+    let values = vec![
+      "10000000000".to_string(),
+      item.sku.clone(),
+      item.product_name.clone(),
+      item.product_type.clone(),
+      item.price.clone(),
+      item.currency.clone(),
+      item.published_status.clone(),
+      String::new(),
+      "ACTIVE".to_string(),
+      inventory,
+      "STANDARD".to_string(),
+      item.wpid.clone(),
+      (i + 1).to_string(),
+      item.gtin.clone(),
+      item.upc.clone(),
+      String::new(),
+      String::new(),
+      String::new(),
+      String::new(),
+      String::new(),
+      String::new(),
+      String::new(),
+      String::new(),
+      "0".to_string(),
+      String::new(),
+      "Yes".to_string(),
+    ];
+    csv.push_str(&get_csv_line(values.into_iter()));
+  }
+
+  let mut zip = ZipWriter::new(Cursor::new(vec![]));
+  zip.start_file("ItemReport.csv", FileOptions::default())?;
+  zip.write_all(csv.as_bytes())?;
+  Ok(zip.finish()?.into_inner())
+}
+
+fn get_csv_line<I: Iterator<Item = String>>(values: I) -> String {
+  let mut line = values
+    .map(|v| {
+      if v.contains(',') || v.contains('"') || v.contains('\n') {
+        format!("\"{}\"", v.replace('"', "\"\""))
+      } else {
+        v
+      }
+    })
+    .collect::<Vec<_>>()
+    .join(",");
+  line.push('\n');
+  line
+}
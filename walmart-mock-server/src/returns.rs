@@ -0,0 +1,245 @@
+//! `/v3/returns`
+
+use crate::error_response;
+use crate::http::{Request, Response};
+use crate::state::State;
+use chrono::{SecondsFormat, Utc};
+use serde_json::Value;
+
+const DEFAULT_LIMIT: usize = 10;
+const RETURN_ERROR: &str = "INVALID_REQUEST_CONTENT.GMP_RETURNS_API";
+
+/// An initiated return with one line of quantity 1 per sku
+pub fn sample_return(return_order_id: &str, customer_order_id: &str, skus: &[&str]) -> Value {
+  let now = Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true);
+  let lines: Vec<Value> = skus
+    .iter()
+    .enumerate()
+    .map(|(i, sku)| {
+      json!({
+        "returnOrderLineNumber": i + 1,
+        "salesOrderLineNumber": i + 1,
+        "returnReason": "DAMAGED_ITEM",
+        "purchaseOrderId": format!("P{}", customer_order_id),
+        "purchaseOrderLineNumber": i + 1,
+        "isReturnForException": false,
+        "item": {
+          "sku": sku,
+          "productName": format!("Product {}", sku),
+          "condition": "NEW"
+        },
+        "unitPrice": {
+          "currencyAmount": 10.0,
+          "currencyUnit": "USD"
+        },
+        "quantity": {
+          "unitOfMeasure": "EACH",
+          "measurementValue": 1
+        },
+        "refundedQty": 0,
+        "status": "INITIATED",
+        "statusTime": now,
+        "currentRefundStatus": "REFUND_PENDING"
+      })
+    })
+    .collect();
+
+  json!({
+    "returnOrderId": return_order_id,
+    "customerEmailId": "customer@relay.walmart.com",
+    "customerName": {
+      "firstName": "Jane",
+      "lastName": "Doe"
+    },
+    "customerOrderId": customer_order_id,
+    "returnOrderDate": now,
+    "returnByDate": now,
+    "refundMode": "POST_RECEIPT",
+    "totalRefundAmount": {
+      "currencyAmount": 10.0 * skus.len() as f64,
+      "currencyUnit": "USD"
+    },
+    "returnOrderLines": lines
+  })
+}
+
+/// `GET /v3/returns`, paged by `offset` in `nextCursor`
+pub fn list(state: &State, req: &Request) -> Response {
+  let return_order_id = req.query_value("returnOrderId");
+  let customer_order_id = req.query_value("customerOrderId");
+  let status = req.query_value("status");
+
+  let returns: Vec<&Value> = state
+    .returns
+    .values()
+    .filter(|order| {
+      if let Some(ref id) = return_order_id {
+        if order["returnOrderId"].as_str() != Some(id) {
+          return false;
+        }
+      }
+      if let Some(ref id) = customer_order_id {
+        if order["customerOrderId"].as_str() != Some(id) {
+          return false;
+        }
+      }
+      if let Some(ref status) = status {
+        if !get_lines(order)
+          .into_iter()
+          .any(|line| line["status"].as_str() == Some(status))
+        {
+          return false;
+        }
+      }
+      true
+    })
+    .collect();
+
+  let limit = req
+    .query_value("limit")
+    .and_then(|v| v.parse().ok())
+    .unwrap_or(DEFAULT_LIMIT);
+  let offset: usize = req
+    .query_value("offset")
+    .and_then(|v| v.parse().ok())
+    .unwrap_or(0);
+  let page: Vec<&Value> = returns.iter().skip(offset).take(limit).cloned().collect();
+
+  let next_offset = offset + page.len();
+  let next_cursor = if next_offset < returns.len() {
+    let mut cursor = format!("?limit={}&offset={}", limit, next_offset);
+    for (k, v) in req.query() {
+      if k != "limit" && k != "offset" {
+        cursor.push_str(&format!("&{}={}", k, v));
+      }
+    }
+    Some(cursor)
+  } else {
+    None
+  };
+
+  Response::json(
+    200,
+    &json!({
+      "meta": {
+        "totalCount": returns.len(),
+        "limit": limit,
+        "nextCursor": next_cursor
+      },
+      "returnOrders": page
+    }),
+  )
+}
+
+/// `POST /v3/returns/{id}/refund`, refunds at most the returned quantity of a line
+pub fn refund(state: &mut State, return_order_id: &str, req: &Request) -> Response {
+  let order = match state.returns.get_mut(return_order_id) {
+    Some(order) => order,
+    None => {
+      return error_response(
+        404,
+        "CONTENT_NOT_FOUND.GMP_RETURNS_API",
+        Some("returnOrderId"),
+        &format!("return order {} was not found", return_order_id),
+      );
+    }
+  };
+
+  let body: Value = match serde_json::from_slice(&req.body) {
+    Ok(body) => body,
+    Err(err) => return bad_request(None, &format!("invalid json: {}", err)),
+  };
+  if body["customerOrderId"] != order["customerOrderId"] {
+    return bad_request(
+      Some("customerOrderId"),
+      "customerOrderId does not match the return order",
+    );
+  }
+  let refund_lines = match body["refundLines"].as_array() {
+    Some(lines) => lines.clone(),
+    None => return bad_request(Some("refundLines"), "refundLines is required"),
+  };
+
+  // validate every line before changing any
+  for refund_line in &refund_lines {
+    let line_number = refund_line["returnOrderLineNumber"].as_u64();
+    let line = match get_lines(order)
+      .into_iter()
+      .find(|line| line["returnOrderLineNumber"].as_u64() == line_number)
+    {
+      Some(line) => line,
+      None => {
+        return bad_request(
+          Some("returnOrderLineNumber"),
+          &format!("line {:?} was not found", line_number),
+        );
+      }
+    };
+    let quantity = refund_line["quantity"]["measurementValue"]
+      .as_f64()
+      .unwrap_or_default();
+    let refundable = line["quantity"]["measurementValue"]
+      .as_f64()
+      .unwrap_or_default()
+      - line["refundedQty"].as_f64().unwrap_or_default();
+    if quantity <= 0.0 || quantity > refundable {
+      return bad_request(
+        Some("quantity"),
+        &format!(
+          "line {:?} can not refund {} units, {} are refundable",
+          line_number, quantity, refundable
+        ),
+      );
+    }
+  }
+
+  let now = Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true);
+  let mut statuses = vec![];
+  for refund_line in refund_lines {
+    let line_number = refund_line["returnOrderLineNumber"].as_u64();
+    if let Some(line) = get_lines_mut(order)
+      .into_iter()
+      .find(|line| line["returnOrderLineNumber"].as_u64() == line_number)
+    {
+      let refunded = line["refundedQty"].as_f64().unwrap_or_default()
+        + refund_line["quantity"]["measurementValue"]
+          .as_f64()
+          .unwrap_or_default();
+      line["refundedQty"] = json!(refunded);
+      line["currentRefundStatus"] = json!("REFUND_COMPLETED");
+      line["status"] = json!("COMPLETED");
+      line["statusTime"] = json!(now);
+    }
+    statuses.push(json!({
+      "returnOrderLineNumber": line_number,
+      "refundStatus": "REFUND_COMPLETED"
+    }));
+  }
+
+  Response::json(
+    200,
+    &json!({
+      "returnOrderId": return_order_id,
+      "customerOrderId": order["customerOrderId"],
+      "refundLines": statuses
+    }),
+  )
+}
+
+fn get_lines(order: &Value) -> Vec<&Value> {
+  order["returnOrderLines"]
+    .as_array()
+    .map(|lines| lines.iter().collect())
+    .unwrap_or_default()
+}
+
+fn get_lines_mut(order: &mut Value) -> Vec<&mut Value> {
+  order["returnOrderLines"]
+    .as_array_mut()
+    .map(|lines| lines.iter_mut().collect())
+    .unwrap_or_default()
+}
+
+fn bad_request(field: Option<&str>, description: &str) -> Response {
+  error_response(400, RETURN_ERROR, field, description)
+}
@@ -0,0 +1,86 @@
+//! Data served by the mock server
+
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+
+/// Number of status polls after which an uploaded feed is `PROCESSED`
+pub const FEED_POLLS_TO_PROCESS: u32 = 2;
+
+/// An item of `/v3/items`, inventory and the item report
+#[derive(Debug, Clone)]
+pub struct MockItem {
+  pub sku: String,
+  pub wpid: String,
+  pub upc: String,
+  pub gtin: String,
+  pub product_name: String,
+  pub product_type: String,
+  pub price: String,
+  pub currency: String,
+  pub published_status: String,
+}
+
+impl MockItem {
+  pub fn new(sku: &str, product_name: &str, price: &str) -> Self {
+    MockItem {
+      sku: sku.to_string(),
+      wpid: format!("WPID-{}", sku),
+      upc: String::new(),
+      gtin: String::new(),
+      product_name: product_name.to_string(),
+      product_type: "Default".to_string(),
+      price: price.to_string(),
+      currency: "USD".to_string(),
+      published_status: "PUBLISHED".to_string(),
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct MockInventory {
+  pub amount: i32,
+  pub fulfillment_lag_time: Option<i32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MockFeed {
+  pub feed_id: String,
+  pub feed_type: String,
+  /// Uploaded request body, multipart encoded
+  pub body: Vec<u8>,
+  pub feed_date: i64,
+  pub polls: u32,
+}
+
+impl MockFeed {
+  pub fn status(&self) -> &'static str {
+    match self.polls {
+      0 => "RECEIVED",
+      n if n < FEED_POLLS_TO_PROCESS => "INPROGRESS",
+      _ => "PROCESSED",
+    }
+  }
+}
+
+#[derive(Debug, Default)]
+pub struct State {
+  /// Walmart order JSON objects, by purchase order id
+  pub orders: BTreeMap<String, Value>,
+  /// Walmart return order JSON objects, by return order id
+  pub returns: BTreeMap<String, Value>,
+  pub items: Vec<MockItem>,
+  pub inventory: BTreeMap<String, MockInventory>,
+  pub feeds: Vec<MockFeed>,
+  /// Report bodies by report type, generated from `items` if not set
+  pub reports: BTreeMap<String, Vec<u8>>,
+  /// Access tokens issued by `/v3/token`
+  pub tokens: HashSet<String>,
+  pub next_id: u64,
+}
+
+impl State {
+  pub fn next_id(&mut self) -> u64 {
+    self.next_id += 1;
+    self.next_id
+  }
+}
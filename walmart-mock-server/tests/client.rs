@@ -0,0 +1,599 @@
+use bigdecimal::BigDecimal;
+use chrono::Utc;
+use std::io::Cursor;
+use std::str::FromStr;
+use std::sync::Arc;
+use walmart_mock_server::{sample_order, sample_return, MockInventory, MockItem, MockServer};
+use walmart_partner_api::feed::{
+  FeedStatusFilter, GetAllFeedStatusesQuery, GetFeedAndItemStatusQuery,
+};
+use walmart_partner_api::inventory::Inventory;
+use walmart_partner_api::item::GetAllItemsQueryParams;
+use walmart_partner_api::order::{
+  CancelParams, CancellationReason, ChargeType, LineShipment, MemoryWatermarkStore, OrderInfo,
+  OrderLineStatusKind, OrderSync, QueryParams, RefundChargeParams, RefundParams, RefundReason,
+  ReleasedQueryParams, ShipNodeType, ShipPackage, ShipParams, WatermarkStore,
+};
+use walmart_partner_api::report::ItemReportType;
+use walmart_partner_api::returns::{ReturnRefundLine, ReturnsQueryParams};
+use walmart_partner_api::{Client, Money, WalmartCredential, WalmartMarketplace};
+
+const CLIENT_ID: &str = "client_id";
+const CLIENT_SECRET: &str = "client_secret";
+const CONSUMER_ID: &str = "f3aead96-d681-41c9-9b81-bb4facacd8f0";
+const PRIVATE_KEY: &str = "MIICdgIBADANBgkqhkiG9w0BAQEFAASCAmAwggJcAgEAAoGBAKzXEfCYdnBNkKAwVbCpg/tR40WixoZtiuEviSEi4+LdnYAAPy57Qw6+9eqJGTh9iCB2wP/I8lWh5TZ49Hq/chjTCPeJiOqi6bvX1xzyBlSq2ElSY3iEVKeVoQG/5f9MYQLEj5/vfTWSNASsMwnNeBbbHcV1S1aY9tOsXCzRuxapAgMBAAECgYBjkM1j1OA9l2Ed9loWl8BQ8X5D6h4E6Gudhx2uugOe9904FGxRIW6iuvy869dchGv7j41ki+SV0dpRw+HKKCjYE6STKpe0YwIm/tml54aNDQ0vQvF8JWILca1a7v3Go6chf3Ib6JPs6KVsUuNo+Yd+jKR9GAKgnDeXS6NZlTBUAQJBANex815VAySumJ/n8xR+h/dZ2V5qGj6wu3Gsdw6eNYKQn3I8AGQw8N4yzDUoFnrQxqDmP3LOyr3/zgOMNTdszIECQQDNIxiZOVl3/Sjyxy9WHMk5qNfSf5iODynv1OlTG+eWao0Wj/NdfLb4pwxRsf4XZFZ1SQNkbNne7+tEO8FTG1YpAkAwNMY2g/ty3E6iFl3ea7UJlBwfnMkGz8rkye3F55f/+UCZcE2KFuIOVv4Kt03m3vg1h6AQkaUAN8acRl6yZ2+BAkEAke2eiRmYANiR8asqjGqr5x2qcm8ceiplXdwrI1kddQ5VUbCTonSewOIszEz/gWp6arLG/ADHOGWaCo8rptAyiQJACXd1ddXUAKs6x3l752tSH8dOde8nDBgF86NGvgUnBiAPPTmJHuhWrmOZmNaB68PsltEiiFwWByGFV+ld9VKmKg==";
+
+fn start() -> MockServer {
+  MockServer::builder()
+    .token_api(CLIENT_ID, CLIENT_SECRET)
+    .signature(CONSUMER_ID, PRIVATE_KEY)
+    .start()
+    .unwrap()
+}
+
+fn token_api_client(server: &MockServer) -> Client {
+  Client::builder(
+    WalmartMarketplace::USA,
+    WalmartCredential::TokenApi {
+      client_id: CLIENT_ID.to_string(),
+      client_secret: CLIENT_SECRET.to_string(),
+    },
+  )
+  .base_url(&server.url())
+  .build()
+  .unwrap()
+}
+
+fn signature_client(server: &MockServer, consumer_id: &str) -> Client {
+  Client::builder(
+    WalmartMarketplace::USA,
+    WalmartCredential::Signature {
+      channel_type: "channel_type".to_string(),
+      consumer_id: consumer_id.to_string(),
+      private_key: PRIVATE_KEY.to_string(),
+    },
+  )
+  .base_url(&server.url())
+  .build()
+  .unwrap()
+}
+
+fn ship_params(line_number: &str) -> ShipParams {
+  ShipParams {
+    lineNumber: line_number.to_string(),
+    shipDateTime: Utc::now(),
+    carrierName: Some("UPS".to_string()),
+    methodCode: "Standard".to_string(),
+    trackingNumber: "1Z999AA10123456784".to_string(),
+    trackingURL: String::new(),
+    otherCarrier: None,
+    unitOfMeasurement: None,
+    amount: None,
+    shipFromCountry: "USA".to_string(),
+  }
+}
+
+#[test]
+fn test_order_lifecycle() {
+  let server = start();
+  server.add_order(sample_order("1001", &["sku-a", "sku-b"]));
+  server.add_order(sample_order("1002", &["sku-a"]));
+  let client = token_api_client(&server);
+
+  let released = client
+    .get_all_released_orders(&ReleasedQueryParams {
+      limit: Some(1),
+      ..Default::default()
+    })
+    .unwrap();
+  assert_eq!(released.get_total_count(), Some(2));
+  assert_eq!(released.elements.len(), 1);
+  let next = client
+    .get_all_released_orders_by_next_cursor(released.get_next_cursor().unwrap())
+    .unwrap();
+  assert_eq!(next.elements[0].purchaseOrderId, "1002");
+
+  let params = ReleasedQueryParams {
+    limit: Some(1),
+    ..Default::default()
+  };
+  let ids: Vec<String> = client
+    .released_orders(&params)
+    .items()
+    .map(|order| order.unwrap().purchaseOrderId)
+    .collect();
+  assert_eq!(ids, vec!["1001", "1002"]);
+
+  let order = client.ack_order("1001").unwrap();
+  let status = &order.orderLines.orderLine[0]
+    .orderLineStatuses
+    .orderLineStatus[0];
+  assert_eq!(status.status, OrderLineStatusKind::Acknowledged);
+
+  let released = client.get_all_released_orders(&Default::default()).unwrap();
+  assert_eq!(released.get_total_count(), Some(1));
+
+  let order = client
+    .ship_order("1001", &[ship_params("1"), ship_params("2")])
+    .unwrap();
+  for line in &order.orderLines.orderLine {
+    let status = &line.orderLineStatuses.orderLineStatus[0];
+    assert_eq!(status.status, OrderLineStatusKind::Shipped);
+    let tracking = status.trackingInfo.as_ref().unwrap();
+    assert_eq!(
+      tracking.trackingNumber.as_ref().unwrap(),
+      "1Z999AA10123456784"
+    );
+    assert_eq!(
+      tracking.trackingURL.as_ref().unwrap(),
+      "https://www.ups.com/track?tracknum=1Z999AA10123456784"
+    );
+  }
+  let stored = server.get_order("1001").unwrap();
+  assert_eq!(
+    stored["orderLines"]["orderLine"][1]["orderLineStatuses"]["orderLineStatus"][0]["status"],
+    "Shipped"
+  );
+
+  let shipped = client
+    .get_all_orders(&QueryParams {
+      status: Some(OrderLineStatusKind::Shipped),
+      ..Default::default()
+    })
+    .unwrap();
+  assert_eq!(shipped.elements.len(), 1);
+  let params = QueryParams {
+    limit: Some(1),
+    ..Default::default()
+  };
+  assert_eq!(client.orders(&params).pages().count(), 2);
+
+  // lines can't be shipped twice or before acknowledgement
+  let err = client.ship_order("1001", &[ship_params("1")]).unwrap_err();
+  assert!(err.find_api_error("INVALID_REQUEST_CONTENT").is_some());
+  let err = client.ship_order("1002", &[ship_params("1")]).unwrap_err();
+  assert!(err.find_api_error("INVALID_REQUEST_CONTENT").is_some());
+
+  let err = client.get_order("404").unwrap_err();
+  assert!(err.find_api_error("CONTENT_NOT_FOUND").is_some());
+}
+
+fn package(amount: u32, tracking_number: &str) -> ShipPackage {
+  ShipPackage {
+    amount,
+    unitOfMeasurement: None,
+    shipDateTime: Utc::now(),
+    carrierName: Some("UPS".to_string()),
+    otherCarrier: None,
+    methodCode: "Standard".to_string(),
+    trackingNumber: tracking_number.to_string(),
+    trackingURL: String::new(),
+  }
+}
+
+#[test]
+fn test_ship_packages() {
+  let server = start();
+  let mut order = sample_order("1001", &["sku-a"]);
+  order["orderLines"]["orderLine"][0]["orderLineQuantity"]["amount"] = "3".into();
+  order["orderLines"]["orderLine"][0]["orderLineStatuses"]["orderLineStatus"][0]
+    ["statusQuantity"]["amount"] = "3".into();
+  server.add_order(order);
+  let client = token_api_client(&server);
+  client.ack_order("1001").unwrap();
+
+  let shipment = |packages| LineShipment {
+    lineNumber: "1".to_string(),
+    shipFromCountry: "USA".to_string(),
+    packages,
+  };
+  let order = client
+    .ship_order_packages(
+      "1001",
+      &[shipment(vec![
+        package(1, "1Z999AA10123456781"),
+        package(1, "1Z999AA10123456782"),
+      ])],
+    )
+    .unwrap();
+  assert_eq!(order.remaining_to_ship().unwrap(), 1);
+  assert_eq!(order.shipped_tracking().len(), 2);
+  assert!(!order.is_fully_shipped().unwrap());
+
+  // validated before calling Walmart
+  let err = client
+    .ship_order_packages("1001", &[shipment(vec![package(2, "1Z999AA10123456783")])])
+    .unwrap_err();
+  assert!(err.api_errors().is_empty());
+  assert!(err.to_string().contains("only 1 remain"));
+  // tracking numbers are only checked on request
+  assert!(shipment(vec![package(1, "1Z003")]).validate().is_err());
+
+  let order = client
+    .ship_order_packages("1001", &[shipment(vec![package(1, "1Z999AA10123456783")])])
+    .unwrap();
+  assert!(order.is_fully_shipped().unwrap());
+  assert_eq!(order.shipped_tracking().len(), 3);
+}
+
+#[test]
+fn test_cancel_order_lines() {
+  let server = start();
+  server.add_order(sample_order("1001", &["sku-a", "sku-b"]));
+  let client = token_api_client(&server);
+
+  let order = client
+    .cancel_order_lines(
+      "1001",
+      &[CancelParams {
+        lineNumber: "2".to_string(),
+        cancellationReason: CancellationReason::SellerCancelOutOfStock,
+        unitOfMeasurement: None,
+        amount: None,
+      }],
+    )
+    .unwrap();
+  let statuses: Vec<_> = order
+    .orderLines
+    .orderLine
+    .iter()
+    .map(|line| line.orderLineStatuses.orderLineStatus[0].status.clone())
+    .collect();
+  assert_eq!(
+    statuses,
+    vec![OrderLineStatusKind::Created, OrderLineStatusKind::Cancelled]
+  );
+  let stored = server.get_order("1001").unwrap();
+  assert_eq!(
+    stored["orderLines"]["orderLine"][1]["orderLineStatuses"]["orderLineStatus"][0]
+      ["cancellationReason"],
+    "SELLER_CANCEL_OUT_OF_STOCK"
+  );
+
+  client.ack_order("1001").unwrap();
+  client.ship_order("1001", &[ship_params("1")]).unwrap();
+  let err = client
+    .cancel_order_lines(
+      "1001",
+      &[CancelParams {
+        lineNumber: "1".to_string(),
+        cancellationReason: CancellationReason::CancelBySeller,
+        unitOfMeasurement: None,
+        amount: None,
+      }],
+    )
+    .unwrap_err();
+  assert!(err.find_api_error("INVALID_REQUEST_CONTENT").is_some());
+}
+
+#[test]
+fn test_refund_order_lines() {
+  let server = start();
+  server.add_order(sample_order("1001", &["sku-a"]));
+  let client = token_api_client(&server);
+  let refund = RefundParams {
+    lineNumber: "1".to_string(),
+    isFullRefund: false,
+    refundComments: Some("damaged box".to_string()),
+    charges: vec![
+      RefundChargeParams::product(
+        RefundReason::DamagedItem,
+        Money::new("USD", BigDecimal::from_str("2.50").unwrap()),
+        None,
+      ),
+      RefundChargeParams::shipping(
+        RefundReason::DamagedItem,
+        Money::new("USD", BigDecimal::from_str("1.99").unwrap()),
+        Some(Money::new("USD", BigDecimal::from_str("0.15").unwrap())),
+      ),
+    ],
+  };
+
+  // only shipped lines can be refunded
+  let err = client
+    .refund_order_lines("1001", std::slice::from_ref(&refund))
+    .unwrap_err();
+  assert!(err.find_api_error("INVALID_REQUEST_CONTENT").is_some());
+
+  client.ack_order("1001").unwrap();
+  client.ship_order("1001", &[ship_params("1")]).unwrap();
+  let order = client.refund_order_lines("1001", &[refund]).unwrap();
+  let refund = order.orderLines.orderLine[0].refund.as_ref().unwrap();
+  assert_eq!(refund.refundComments.as_ref().unwrap(), "damaged box");
+  let charges = &refund.refundCharges.refundCharge;
+  assert_eq!(charges[0].refundReason, RefundReason::DamagedItem);
+  assert_eq!(charges[1].charge.chargeType, ChargeType::Shipping);
+  assert_eq!(
+    charges[1].charge.chargeAmount.amount,
+    BigDecimal::from_str("-1.99").unwrap()
+  );
+  assert_eq!(
+    charges[1].charge.tax.as_ref().unwrap().taxAmount.amount,
+    BigDecimal::from_str("-0.15").unwrap()
+  );
+}
+
+#[test]
+fn test_us_wfs_orders() {
+  let server = start();
+  server.add_order(sample_order("4001", &["sku-a"]));
+  let mut wfs = sample_order("4002", &["sku-b"]);
+  wfs["shipNode"] = serde_json::json!({ "type": "WFSFulfilled" });
+  server.add_order(wfs);
+  let client = token_api_client(&server);
+
+  fn describe<T: OrderInfo>(order: &T) -> (Option<String>, bool) {
+    (
+      order.get_purchase_order_id().map(ToString::to_string),
+      order.is_wfs(),
+    )
+  }
+
+  let orders = client.get_all_wfs_orders(&Default::default()).unwrap();
+  let orders: Vec<_> = orders.elements.iter().map(describe).collect();
+  assert_eq!(orders, vec![(Some("4002".to_string()), true)]);
+
+  let orders = client.get_all_orders(&Default::default()).unwrap();
+  let orders: Vec<_> = orders.elements.iter().map(describe).collect();
+  assert_eq!(orders, vec![(Some("4001".to_string()), false)]);
+
+  let params = QueryParams {
+    shipNodeType: Some(ShipNodeType::WFSFulfilled),
+    ..Default::default()
+  };
+  let orders = client.get_all_orders(&params).unwrap();
+  assert_eq!(orders.elements[0].purchaseOrderId, "4002");
+}
+
+#[test]
+fn test_ack_all_released() {
+  let server = start();
+  for id in &["3001", "3002", "3003", "3004"] {
+    server.add_order(sample_order(id, &["sku-a"]));
+  }
+  let client = token_api_client(&server);
+  client.ack_order("3002").unwrap();
+
+  let results = client.ack_all_released(2).unwrap();
+  let ids: Vec<&str> = results
+    .iter()
+    .map(|r| r.purchase_order_id.as_str())
+    .collect();
+  assert_eq!(ids, vec!["3001", "3003", "3004"]);
+  assert!(results.iter().all(|r| r.result.is_ok()));
+  for id in &["3001", "3003", "3004"] {
+    assert_eq!(
+      server.get_order(id).unwrap()["orderLines"]["orderLine"][0]["orderLineStatuses"]
+        ["orderLineStatus"][0]["status"],
+      "Acknowledged"
+    );
+  }
+  assert!(client.ack_all_released(2).unwrap().is_empty());
+}
+
+#[test]
+fn test_order_sync() {
+  let server = start();
+  server.add_order(sample_order("2001", &["sku-a"]));
+  server.add_order(sample_order("2002", &["sku-b"]));
+  let client = token_api_client(&server);
+  let store = Arc::new(MemoryWatermarkStore::new());
+  let sync = OrderSync::with_store(&client, store.clone()).limit(1);
+
+  let run = || {
+    let mut ids = vec![];
+    let report = sync
+      .run(|order| {
+        ids.push(order.purchaseOrderId);
+        Ok(())
+      })
+      .unwrap();
+    (ids, report)
+  };
+
+  let (ids, report) = run();
+  assert_eq!(ids, vec!["2001", "2002"]);
+  assert_eq!(report.skipped, 0);
+
+  // orders in the overlap window are not yielded twice
+  let (ids, report) = run();
+  assert!(ids.is_empty());
+  assert_eq!(report.skipped, 2);
+
+  // new and changed orders
+  std::thread::sleep(std::time::Duration::from_millis(5));
+  client.ack_order("2001").unwrap();
+  server.add_order(sample_order("2003", &["sku-c"]));
+  let (mut ids, _) = run();
+  ids.sort();
+  assert_eq!(ids, vec!["2001", "2003"]);
+  assert_eq!(store.get("orders").unwrap().unwrap().recent.len(), 3);
+}
+
+#[test]
+fn test_returns() {
+  let server = start();
+  server.add_return(sample_return("R1", "C1", &["sku-a", "sku-b"]));
+  server.add_return(sample_return("R2", "C2", &["sku-a"]));
+  server.add_return(sample_return("R3", "C3", &["sku-c"]));
+  let client = token_api_client(&server);
+
+  let params = ReturnsQueryParams {
+    limit: Some(2),
+    ..Default::default()
+  };
+  let ids: Vec<String> = client
+    .returns(&params)
+    .items()
+    .map(|order| order.unwrap().returnOrderId)
+    .collect();
+  assert_eq!(ids, vec!["R1", "R2", "R3"]);
+
+  let order = client.get_return("R1").unwrap();
+  assert_eq!(order.customerOrderId, "C1");
+  assert_eq!(order.returnOrderLines.len(), 2);
+  assert_eq!(
+    order.returnOrderLines[0]
+      .unitPrice
+      .as_ref()
+      .unwrap()
+      .to_money()
+      .to_string(),
+    "10 USD"
+  );
+  assert!(client.get_return("R404").is_err());
+
+  let line = ReturnRefundLine {
+    returnOrderLineNumber: 2,
+    quantity: 1,
+  };
+  let refund = client
+    .refund_return("R1", "C1", std::slice::from_ref(&line))
+    .unwrap();
+  assert_eq!(refund.refundLines[0].returnOrderLineNumber, 2);
+  let stored = server.get_return("R1").unwrap();
+  assert_eq!(stored["returnOrderLines"][1]["refundedQty"], 1.0);
+
+  // a line can't be refunded twice
+  let err = client.refund_return("R1", "C1", &[line]).unwrap_err();
+  assert!(err.find_api_error("INVALID_REQUEST_CONTENT").is_some());
+}
+
+#[test]
+fn test_signature_auth() {
+  let server = start();
+  server.add_order(sample_order("1001", &["sku-a"]));
+
+  let client = signature_client(&server, CONSUMER_ID);
+  let order = client.get_order("1001").unwrap();
+  assert_eq!(order.purchaseOrderId, "1001");
+
+  // the signature covers the consumer id
+  let client = signature_client(&server, "another-consumer-id");
+  let err = client.get_order("1001").unwrap_err();
+  assert!(err.find_api_error("UNAUTHORIZED").is_some());
+}
+
+#[test]
+fn test_token_renewal() {
+  let server = start();
+  server.add_order(sample_order("1001", &["sku-a"]));
+  let client = token_api_client(&server);
+
+  client.get_order("1001").unwrap();
+  server.expire_tokens();
+  client.get_order("1001").unwrap();
+}
+
+#[test]
+fn test_items_inventory_and_report() {
+  let server = start();
+  for i in 0..3 {
+    server.add_item(MockItem::new(&format!("sku-{}", i), "Product", "9.99"));
+  }
+  let client = token_api_client(&server);
+
+  let params = GetAllItemsQueryParams {
+    limit: Some(2),
+    ..Default::default()
+  };
+  assert_eq!(client.items(&params).pages().count(), 2);
+  let skus: Vec<String> = client
+    .items(&params)
+    .items()
+    .map(|item| item.unwrap().sku)
+    .collect();
+  assert_eq!(skus, vec!["sku-0", "sku-1", "sku-2"]);
+
+  let err = match client.get_all_items(&GetAllItemsQueryParams {
+    sku: Some("missing".to_string()),
+    ..Default::default()
+  }) {
+    Ok(_) => panic!("item should not be found"),
+    Err(err) => err,
+  };
+  assert!(err.find_api_error("CONTENT_NOT_FOUND").is_some());
+
+  client
+    .update_item_inventory(&Inventory::new("sku-1", 7, 1))
+    .unwrap();
+  assert_eq!(server.get_inventory("sku-1").unwrap().amount, 7);
+  let inventory = client.get_item_inventory("sku-1").unwrap();
+  assert_eq!(inventory.quantity.amount, 7);
+  server.set_inventory(
+    "sku-2",
+    MockInventory {
+      amount: 3,
+      fulfillment_lag_time: None,
+    },
+  );
+
+  let report = client.get_report::<ItemReportType>().unwrap();
+  assert_eq!(report.rows.len(), 3);
+  assert_eq!(report.rows[1].inventory_count, Some(7));
+  assert_eq!(report.rows[2].inventory_count, Some(3));
+}
+
+#[test]
+fn test_feed_processing() {
+  let server = start();
+  let client = token_api_client(&server);
+
+  let ack = client
+    .bulk_upload_xml("item", Cursor::new(b"<MPItemFeed/>".to_vec()))
+    .unwrap();
+  let feeds = server.get_feeds();
+  assert_eq!(feeds[0].feed_type, "item");
+  assert!(String::from_utf8_lossy(&feeds[0].body).contains("<MPItemFeed/>"));
+
+  let mut statuses = vec![];
+  loop {
+    let status = client
+      .get_feed_and_item_status(&ack.feedId, &GetFeedAndItemStatusQuery::default())
+      .unwrap();
+    statuses.push(status.feedStatus.clone());
+    if status.feedStatus == "PROCESSED" {
+      break;
+    }
+  }
+  assert_eq!(statuses, vec!["RECEIVED", "INPROGRESS", "PROCESSED"]);
+}
+
+#[test]
+fn test_feed_statuses() {
+  let server = start();
+  let client = token_api_client(&server);
+  for feed_type in &["item", "inventory", "item"] {
+    client
+      .bulk_upload_xml(feed_type, Cursor::new(b"<MPItemFeed/>".to_vec()))
+      .unwrap();
+  }
+
+  let query = GetAllFeedStatusesQuery {
+    limit: Some(1),
+    ..Default::default()
+  };
+  let pages = client
+    .feed_statuses(&query, FeedStatusFilter::default())
+    .pages()
+    .count();
+  assert_eq!(pages, 3);
+
+  let filter = FeedStatusFilter {
+    feed_type: Some("item".to_string()),
+    ..Default::default()
+  };
+  let statuses: Vec<_> = client
+    .feed_statuses(&query, filter)
+    .items()
+    .map(Result::unwrap)
+    .collect();
+  assert_eq!(statuses.len(), 2);
+  assert!(statuses.iter().all(|status| status.feedType == "item"));
+
+  let filter = FeedStatusFilter {
+    feed_date_to: Some(Utc::now() - chrono::Duration::hours(1)),
+    ..Default::default()
+  };
+  assert_eq!(client.feed_statuses(&query, filter).items().count(), 0);
+}
@@ -0,0 +1,182 @@
+//! Async client built on `reqwest`'s async client
+//!
+//! Futures returned by `AsyncClient` must be run on a tokio runtime.
+
+use crate::client::{ClientState, ExtendUrlParams, WalmartBearerToken, WalmartMarketplace};
+use crate::result::*;
+use futures::{future, Future, Stream};
+use reqwest::header::{HeaderValue, ACCEPT};
+use reqwest::r#async::{Client as HttpClient, RequestBuilder, Response};
+use reqwest::{Method, StatusCode};
+use std::sync::Arc;
+
+/// Returns a failed `WalmartFuture` early if the expression is an `Err`
+macro_rules! try_future {
+  ($e:expr) => {
+    match $e {
+      Ok(v) => v,
+      Err(err) => return Box::new(::futures::future::err(err.into())),
+    }
+  };
+}
+
+pub type WalmartFuture<T> = Box<dyn Future<Item = T, Error = WalmartError> + Send>;
+
+struct Inner {
+  state: ClientState,
+  http: HttpClient,
+}
+
+/// Async version of `Client`
+///
+/// Cloning is cheap, clones share the http connection pool and the cached access token.
+#[derive(Clone)]
+pub struct AsyncClient {
+  inner: Arc<Inner>,
+}
+
+impl AsyncClient {
+  pub(crate) fn new(state: ClientState, http: HttpClient) -> Self {
+    AsyncClient {
+      inner: Arc::new(Inner { state, http }),
+    }
+  }
+
+  fn request<P>(&self, method: Method, path: &str, params: P) -> WalmartFuture<RequestBuilder>
+  where
+    P: ExtendUrlParams,
+  {
+    let url = try_future!(self.inner.state.get_url(path, params));
+
+    let client = self.clone();
+    let access_token: WalmartFuture<Option<String>> = if self.inner.state.use_access_token() {
+      Box::new(self.get_access_token(false).map(Some))
+    } else {
+      Box::new(future::ok(None))
+    };
+
+    Box::new(access_token.and_then(move |access_token| {
+      let headers = client.inner.state.get_request_headers(
+        &method,
+        &url,
+        access_token.as_ref().map(AsRef::as_ref),
+      )?;
+      Ok(
+        client
+          .inner
+          .http
+          .request(method, url.as_str())
+          .headers(headers),
+      )
+    }))
+  }
+
+  fn get_access_token(&self, force_renew: bool) -> WalmartFuture<String> {
+    if !force_renew {
+      if let Some(access_token) = self.inner.state.get_cached_access_token() {
+        return Box::new(future::ok(access_token));
+      }
+    }
+
+    let state = &self.inner.state;
+    let req = state.get_token_url().and_then(|url| {
+      Ok(
+        self
+          .inner
+          .http
+          .request(Method::POST, url)
+          .headers(state.get_token_request_headers()?)
+          .form(&state.get_token_form()),
+      )
+    });
+    let req = try_future!(req);
+
+    let client = self.clone();
+    Box::new(
+      req
+        .send()
+        .and_then(|mut res| res.json::<WalmartBearerToken>())
+        .from_err()
+        .and_then(move |token| client.inner.state.set_access_token(token)),
+    )
+  }
+
+  pub fn request_json<P>(
+    &self,
+    method: Method,
+    path: &str,
+    params: P,
+  ) -> WalmartFuture<RequestBuilder>
+  where
+    P: ExtendUrlParams,
+  {
+    Box::new(
+      self
+        .request(method, path, params)
+        .map(|req| req.header(ACCEPT, HeaderValue::from_static("application/json"))),
+    )
+  }
+
+  pub fn request_xml<P>(
+    &self,
+    method: Method,
+    path: &str,
+    params: P,
+  ) -> WalmartFuture<RequestBuilder>
+  where
+    P: ExtendUrlParams,
+  {
+    Box::new(
+      self
+        .request(method, path, params)
+        .map(|req| req.header(ACCEPT, HeaderValue::from_static("application/xml"))),
+    )
+  }
+
+  pub fn send(&self, req: RequestBuilder) -> WalmartFuture<Response> {
+    let client = self.clone();
+    Box::new(req.send().from_err().map(move |res| {
+      if res.status() == StatusCode::UNAUTHORIZED {
+        client.inner.state.clear_access_token();
+      }
+      res
+    }))
+  }
+
+  /// Sends the request and passes the status and the whole response body to `parse`
+  pub(crate) fn send_parse<T, F>(
+    &self,
+    req: WalmartFuture<RequestBuilder>,
+    parse: F,
+  ) -> WalmartFuture<T>
+  where
+    T: Send + 'static,
+    F: FnOnce(StatusCode, Vec<u8>) -> WalmartResult<T> + Send + 'static,
+  {
+    let client = self.clone();
+    Box::new(
+      req
+        .and_then(move |req| client.send(req))
+        .and_then(|res| {
+          let status = res.status();
+          res
+            .into_body()
+            .concat2()
+            .from_err()
+            .map(move |body| (status, body.to_vec()))
+        })
+        .and_then(move |(status, body)| parse(status, body)),
+    )
+  }
+
+  pub fn get_marketplace(&self) -> WalmartMarketplace {
+    self.inner.state.marketplace
+  }
+}
+
+/// Converts a response body to `String` for the shared response parsers
+pub(crate) fn body_to_string(status: StatusCode, body: Vec<u8>) -> WalmartResult<String> {
+  String::from_utf8(body).map_err(|err| {
+    ApiResponseError::new(status, format!("read response: {}", err), "".to_owned()).into()
+  })
+}
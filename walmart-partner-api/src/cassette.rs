@@ -0,0 +1,313 @@
+//! Recording and offline replay of API interactions
+//!
+//! In record mode `Client` writes every request and response to a JSON cassette file,
+//! with credentials and access tokens redacted. In replay mode responses are served from
+//! the cassette instead of the network, so tests run offline and deterministically.
+//!
+//! Requests are matched by method, path and query, in recorded order.
+//! Request bodies are not recorded.
+
+use crate::result::*;
+use crate::utils::write_json_file;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Method, Request, Response, StatusCode, Url};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const REDACTED: &str = "REDACTED";
+
+/// Headers carrying credentials
+const SENSITIVE_HEADERS: &[&str] = &[
+  "authorization",
+  "wm_sec.auth_signature",
+  "wm_sec.access_token",
+  "wm_consumer.id",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CassetteMode {
+  /// Send requests and write the interactions to the cassette file
+  Record,
+  /// Serve responses from the cassette file without sending requests
+  Replay,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedRequest {
+  pub method: String,
+  pub url: String,
+  #[serde(default)]
+  pub headers: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedResponse {
+  pub status: u16,
+  #[serde(default)]
+  pub headers: BTreeMap<String, String>,
+  /// Text body
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub body: Option<String>,
+  /// Binary body like a zipped report, base64 encoded
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub body_base64: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+  pub request: RecordedRequest,
+  pub response: RecordedResponse,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CassetteFile {
+  interactions: Vec<Interaction>,
+}
+
+#[derive(Debug)]
+struct State {
+  interactions: Vec<Interaction>,
+  used: Vec<bool>,
+}
+
+/// A cassette file, set on a client with `ClientBuilder::cassette`
+#[derive(Debug)]
+pub struct Cassette {
+  mode: CassetteMode,
+  path: PathBuf,
+  state: Mutex<State>,
+}
+
+impl Cassette {
+  /// Records interactions to `path`, replacing the existing file
+  pub fn record<P: AsRef<Path>>(path: P) -> Self {
+    Cassette {
+      mode: CassetteMode::Record,
+      path: path.as_ref().to_path_buf(),
+      state: Mutex::new(State {
+        interactions: vec![],
+        used: vec![],
+      }),
+    }
+  }
+
+  /// Loads recorded interactions from `path`
+  pub fn replay<P: AsRef<Path>>(path: P) -> WalmartResult<Self> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path)?;
+    let file: CassetteFile = serde_json::from_str(&content)
+      .map_err(|err| WalmartError::Msg(format!("parse cassette '{}': {}", path.display(), err)))?;
+    let used = vec![false; file.interactions.len()];
+    Ok(Cassette {
+      mode: CassetteMode::Replay,
+      path: path.to_path_buf(),
+      state: Mutex::new(State {
+        interactions: file.interactions,
+        used,
+      }),
+    })
+  }
+
+  pub fn mode(&self) -> CassetteMode {
+    self.mode
+  }
+
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  /// Returns the first unused interaction matching the request
+  pub(crate) fn replay_request(&self, req: &Request) -> WalmartResult<Response> {
+    let key = get_request_key(req.method().as_str(), req.url());
+    let mut state = self.state.lock().unwrap();
+    let State {
+      ref interactions,
+      ref mut used,
+    } = *state;
+    let index = interactions
+      .iter()
+      .enumerate()
+      .position(|(i, interaction)| {
+        !used[i]
+          && Url::parse(&interaction.request.url)
+            .map(|url| get_request_key(&interaction.request.method, &url) == key)
+            .unwrap_or(false)
+      })
+      .ok_or_else(|| {
+        WalmartError::Msg(format!(
+          "no interaction in cassette '{}' matches request: {}",
+          self.path.display(),
+          key
+        ))
+      })?;
+    used[index] = true;
+    interactions[index].response.to_response()
+  }
+
+  /// Records the interaction and returns an equivalent response
+  pub(crate) fn record_response(
+    &self,
+    method: &Method,
+    url: &Url,
+    req_headers: &HeaderMap,
+    mut res: Response,
+  ) -> WalmartResult<Response> {
+    let status = res.status();
+    let headers = res.headers().clone();
+    let mut body = vec![];
+    res.copy_to(&mut body)?;
+
+    let interaction = Interaction {
+      request: RecordedRequest {
+        method: method.to_string(),
+        url: url.to_string(),
+        headers: get_recorded_headers(req_headers),
+      },
+      response: RecordedResponse::new(status, &headers, url, &body),
+    };
+
+    {
+      let mut state = self.state.lock().unwrap();
+      state.interactions.push(interaction);
+      state.used.push(true);
+      self.write(&state.interactions)?;
+    }
+
+    Ok(build_response(status, &headers, body))
+  }
+
+  /// Writes the whole cassette after every interaction, so it's complete even if the test fails
+  fn write(&self, interactions: &[Interaction]) -> WalmartResult<()> {
+    write_json_file(
+      &self.path,
+      &CassetteFile {
+        interactions: interactions.to_vec(),
+      },
+    )
+  }
+}
+
+impl RecordedResponse {
+  fn new(status: StatusCode, headers: &HeaderMap, url: &Url, body: &[u8]) -> Self {
+    let mut res = RecordedResponse {
+      status: status.as_u16(),
+      headers: get_recorded_headers(headers),
+      body: None,
+      body_base64: None,
+    };
+    match ::std::str::from_utf8(body) {
+      Ok(text) => res.body = Some(redact_token_response(url, text)),
+      Err(_) => res.body_base64 = Some(base64::encode(body)),
+    }
+    res
+  }
+
+  fn to_response(&self) -> WalmartResult<Response> {
+    let status = StatusCode::from_u16(self.status)
+      .map_err(|err| WalmartError::Msg(format!("invalid recorded status: {}", err)))?;
+    let mut headers = HeaderMap::new();
+    for (name, value) in &self.headers {
+      headers.insert(
+        name.parse::<HeaderName>().map_err(|err| {
+          WalmartError::Msg(format!("invalid recorded header '{}': {}", name, err))
+        })?,
+        HeaderValue::from_str(value)?,
+      );
+    }
+    let body = match (&self.body, &self.body_base64) {
+      (_, Some(encoded)) => base64::decode(encoded)?,
+      (Some(text), None) => text.clone().into_bytes(),
+      (None, None) => vec![],
+    };
+    Ok(build_response(status, &headers, body))
+  }
+}
+
+fn build_response(status: StatusCode, headers: &HeaderMap, body: Vec<u8>) -> Response {
+  let mut res = ::http::Response::new(body);
+  *res.status_mut() = status;
+  *res.headers_mut() = headers.clone();
+  Response::from(res)
+}
+
+fn get_request_key(method: &str, url: &Url) -> String {
+  match url.query() {
+    Some(query) => format!("{} {}?{}", method, url.path(), query),
+    None => format!("{} {}", method, url.path()),
+  }
+}
+
+fn get_recorded_headers(headers: &HeaderMap) -> BTreeMap<String, String> {
+  headers
+    .iter()
+    .filter_map(|(name, value)| {
+      let name = name.as_str().to_string();
+      if SENSITIVE_HEADERS.contains(&name.as_str()) {
+        return Some((name, REDACTED.to_string()));
+      }
+      value.to_str().ok().map(|value| (name, value.to_string()))
+    })
+    .collect()
+}
+
+/// Replaces the access token returned by the Token API
+fn redact_token_response(url: &Url, body: &str) -> String {
+  if !url.path().ends_with("/token") {
+    return body.to_string();
+  }
+  match serde_json::from_str::<Value>(body) {
+    Ok(Value::Object(mut obj)) => {
+      if obj.contains_key("access_token") {
+        obj.insert(
+          "access_token".to_string(),
+          Value::String(REDACTED.to_string()),
+        );
+      }
+      Value::Object(obj).to_string()
+    }
+    _ => body.to_string(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use tempfile::NamedTempFile;
+
+  #[test]
+  fn test_record_and_replay() {
+    let file = NamedTempFile::new().unwrap();
+    let cassette = Cassette::record(file.path());
+
+    let url = Url::parse("https://marketplace.walmartapis.com/v3/token").unwrap();
+    let mut headers = HeaderMap::new();
+    headers.insert("Authorization", "Basic c2VjcmV0".parse().unwrap());
+    let body = r#"{"access_token":"secret","token_type":"Bearer","expires_in":900}"#;
+    let res = build_response(StatusCode::OK, &HeaderMap::new(), body.as_bytes().to_vec());
+    let mut res = cassette
+      .record_response(&Method::POST, &url, &headers, res)
+      .unwrap();
+    assert_eq!(res.text().unwrap(), body);
+
+    let content = fs::read_to_string(file.path()).unwrap();
+    assert!(!content.contains("secret"));
+    assert!(!content.contains("c2VjcmV0"));
+
+    let cassette = Cassette::replay(file.path()).unwrap();
+    let req = Request::new(
+      Method::POST,
+      Url::parse("http://localhost/v3/token").unwrap(),
+    );
+    let mut res = cassette.replay_request(&req).unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let value: Value = res.json().unwrap();
+    assert_eq!(value["access_token"], REDACTED);
+    assert_eq!(value["expires_in"], 900);
+
+    // every interaction is served once
+    assert!(cassette.replay_request(&req).is_err());
+  }
+}
@@ -1,14 +1,21 @@
+#[cfg(feature = "async")]
+use crate::async_client::AsyncClient;
+use crate::cassette::{Cassette, CassetteMode};
+use crate::rate_limit::{RateLimitMode, RateLimiter};
 use crate::result::*;
+use crate::retry::{get_retry_after, RetryPolicy};
 use crate::sign::Signature;
+use crate::token_store::{MemoryTokenStore, StoredToken, TokenStore};
 use chrono::Utc;
 use rand::{thread_rng, Rng};
 use reqwest;
-use reqwest::header::HeaderMap;
+use reqwest::header::{HeaderMap, AUTHORIZATION, USER_AGENT};
 pub use reqwest::{Method, Request, RequestBuilder, Response, StatusCode, Url};
-use std::sync::RwLock;
-use std::time::{Duration, Instant};
+use std::sync::Arc;
+use std::time::Duration;
 
-const BASE_URL: &'static str = "https://marketplace.walmartapis.com";
+const BASE_URL: &str = "https://marketplace.walmartapis.com";
+const SERVICE_NAME: &str = "Walmart Marketplace";
 
 #[derive(Debug, Clone, Copy)]
 pub enum WalmartMarketplace {
@@ -28,11 +35,11 @@ pub enum WalmartCredential {
   },
 }
 
-enum AuthState {
+pub(crate) enum AuthState {
   TokenApi {
     client_id: String,
     client_secret: String,
-    bearer_token: RwLock<Option<BearerToken>>,
+    token_store: Arc<dyn TokenStore>,
   },
   Signature {
     channel_type: String,
@@ -40,9 +47,11 @@ enum AuthState {
   },
 }
 
-struct BearerToken {
+#[derive(Debug, Deserialize)]
+pub(crate) struct WalmartBearerToken {
   access_token: String,
-  expires_at: Instant,
+  token_type: String,
+  expires_in: u64,
 }
 
 pub trait ExtendUrlParams {
@@ -73,31 +82,173 @@ impl<T1: AsRef<str>, T2: AsRef<str>> ExtendUrlParams for Vec<(T1, T2)> {
   }
 }
 
-pub struct Client {
+/// Builder for `Client`
+pub struct ClientBuilder {
   marketplace: WalmartMarketplace,
-  base_url: Url,
-  auth_state: AuthState,
-  http: reqwest::Client,
+  credential: WalmartCredential,
+  base_url: String,
+  service_name: String,
+  timeout: Option<Duration>,
+  connect_timeout: Option<Duration>,
+  proxy: Option<reqwest::Proxy>,
+  user_agent: Option<String>,
+  retry_policy: RetryPolicy,
+  rate_limit_mode: RateLimitMode,
+  token_store: Option<Arc<dyn TokenStore>>,
+  cassette: Option<Cassette>,
+  http: Option<reqwest::Client>,
 }
 
-impl Client {
-  pub fn new(
-    marketplace: WalmartMarketplace,
-    credential: WalmartCredential,
-  ) -> WalmartResult<Client> {
-    let http = reqwest::Client::new();
-    Client::with_http_client(marketplace, credential, http)
+impl ClientBuilder {
+  pub fn new(marketplace: WalmartMarketplace, credential: WalmartCredential) -> Self {
+    ClientBuilder {
+      marketplace,
+      credential,
+      base_url: BASE_URL.to_string(),
+      service_name: SERVICE_NAME.to_string(),
+      timeout: None,
+      connect_timeout: None,
+      proxy: None,
+      user_agent: None,
+      retry_policy: RetryPolicy::default(),
+      rate_limit_mode: RateLimitMode::default(),
+      token_store: None,
+      cassette: None,
+      http: None,
+    }
   }
 
-  pub fn with_http_client(
-    marketplace: WalmartMarketplace,
-    credential: WalmartCredential,
-    http: reqwest::Client,
-  ) -> WalmartResult<Client> {
+  /// Sets the API base url, e.g. Walmart's sandbox host or a local mock server.
+  /// All request paths, including the token endpoint, are joined onto it.
+  pub fn base_url(mut self, base_url: &str) -> Self {
+    self.base_url = base_url.to_string();
+    self
+  }
+
+  /// Sets the `WM_SVC.NAME` header value
+  pub fn service_name(mut self, service_name: &str) -> Self {
+    self.service_name = service_name.to_string();
+    self
+  }
+
+  /// Sets the total request timeout
+  pub fn timeout(mut self, timeout: Duration) -> Self {
+    self.timeout = Some(timeout);
+    self
+  }
+
+  pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+    self.connect_timeout = Some(timeout);
+    self
+  }
+
+  pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+    self.proxy = Some(proxy);
+    self
+  }
+
+  pub fn user_agent(mut self, user_agent: &str) -> Self {
+    self.user_agent = Some(user_agent.to_string());
+    self
+  }
+
+  /// Sets the retry policy of `Client::send`, `RetryPolicy::default()` if not set.
+  /// `AsyncClient` doesn't retry requests.
+  pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+    self.retry_policy = retry_policy;
+    self
+  }
+
+  /// Sets how `Client::send` handles requests that would be throttled,
+  /// `RateLimitMode::Block` if not set. `AsyncClient` doesn't limit requests.
+  pub fn rate_limit_mode(mut self, mode: RateLimitMode) -> Self {
+    self.rate_limit_mode = mode;
+    self
+  }
+
+  /// Sets where Token API bearer tokens are kept, `MemoryTokenStore` if not set
+  pub fn token_store(mut self, token_store: Arc<dyn TokenStore>) -> Self {
+    self.token_store = Some(token_store);
+    self
+  }
+
+  /// Records interactions to, or replays them from, a cassette file.
+  /// `AsyncClient` ignores the cassette.
+  pub fn cassette(mut self, cassette: Cassette) -> Self {
+    self.cassette = Some(cassette);
+    self
+  }
+
+  /// Uses an existing http client.
+  /// `timeout`, `connect_timeout`, `proxy` and `user_agent` are ignored in this case.
+  pub fn http_client(mut self, http: reqwest::Client) -> Self {
+    self.http = Some(http);
+    self
+  }
+
+  pub fn build(mut self) -> WalmartResult<Client> {
+    let http = match self.http.take() {
+      Some(http) => http,
+      None => {
+        let mut builder = reqwest::Client::builder();
+        if let Some(timeout) = self.timeout {
+          builder = builder.timeout(timeout);
+        }
+        if let Some(timeout) = self.connect_timeout {
+          builder = builder.connect_timeout(timeout);
+        }
+        if let Some(proxy) = self.proxy.take() {
+          builder = builder.proxy(proxy);
+        }
+        if let Some(user_agent) = self.user_agent.take() {
+          let mut headers = HeaderMap::new();
+          headers.insert(USER_AGENT, user_agent.parse()?);
+          builder = builder.default_headers(headers);
+        }
+        builder.build()?
+      }
+    };
+
     Ok(Client {
-      marketplace,
-      base_url: Url::parse(BASE_URL)?,
-      auth_state: match credential {
+      retry_policy: self.retry_policy.clone(),
+      rate_limiter: RateLimiter::new(self.rate_limit_mode),
+      cassette: self.cassette.take(),
+      state: self.state()?,
+      http,
+    })
+  }
+
+  /// Builds an `AsyncClient` with the same configuration.
+  /// The http client set by `http_client` is ignored.
+  #[cfg(feature = "async")]
+  pub fn build_async(mut self) -> WalmartResult<AsyncClient> {
+    use reqwest::r#async::Client as AsyncHttpClient;
+
+    let mut builder = AsyncHttpClient::builder();
+    if let Some(timeout) = self.timeout {
+      builder = builder.timeout(timeout);
+    }
+    if let Some(timeout) = self.connect_timeout {
+      builder = builder.connect_timeout(timeout);
+    }
+    if let Some(proxy) = self.proxy.take() {
+      builder = builder.proxy(proxy);
+    }
+    if let Some(user_agent) = self.user_agent.take() {
+      let mut headers = HeaderMap::new();
+      headers.insert(USER_AGENT, user_agent.parse()?);
+      builder = builder.default_headers(headers);
+    }
+    let http = builder.build()?;
+    Ok(AsyncClient::new(self.state()?, http))
+  }
+
+  fn state(self) -> WalmartResult<ClientState> {
+    Ok(ClientState {
+      marketplace: self.marketplace,
+      base_url: Url::parse(&self.base_url)?,
+      service_name: self.service_name,
+      auth_state: match self.credential {
         WalmartCredential::Signature {
           consumer_id,
           private_key,
@@ -112,14 +263,25 @@ impl Client {
         } => AuthState::TokenApi {
           client_id,
           client_secret,
-          bearer_token: RwLock::new(None),
+          token_store: self
+            .token_store
+            .unwrap_or_else(|| Arc::new(MemoryTokenStore::new())),
         },
       },
-      http,
     })
   }
+}
 
-  fn request<P>(&self, method: Method, path: &str, params: P) -> WalmartResult<RequestBuilder>
+/// Configuration and authentication state shared by `Client` and `AsyncClient`
+pub(crate) struct ClientState {
+  pub marketplace: WalmartMarketplace,
+  pub base_url: Url,
+  pub service_name: String,
+  pub auth_state: AuthState,
+}
+
+impl ClientState {
+  pub fn get_url<P>(&self, path: &str, params: P) -> WalmartResult<Url>
   where
     P: ExtendUrlParams,
   {
@@ -143,17 +305,33 @@ impl Client {
       }
     };
     params.extend_url_params(&mut url);
+    Ok(url)
+  }
+
+  /// Returns `true` if requests need a bearer token from the Token API
+  pub fn use_access_token(&self) -> bool {
+    match self.auth_state {
+      AuthState::TokenApi { .. } => true,
+      AuthState::Signature { .. } => false,
+    }
+  }
 
+  /// Builds the authentication headers of an API request.
+  /// `access_token` is required for Token API authentication.
+  pub fn get_request_headers(
+    &self,
+    method: &Method,
+    url: &Url,
+    access_token: Option<&str>,
+  ) -> WalmartResult<HeaderMap> {
     debug!("request: method = {}, url = {}", method, url);
 
     let timestamp = Utc::now();
     let timestamp = timestamp.timestamp() * 1000 + timestamp.timestamp_subsec_millis() as i64;
 
-    let mut req = self.http.request(method.clone(), url.as_str());
-
     let mut headers = HeaderMap::new();
     let rid: String = thread_rng().gen_ascii_chars().take(10).collect();
-    headers.insert("WM_SVC.NAME", "Walmart Marketplace".parse()?);
+    headers.insert("WM_SVC.NAME", self.service_name.parse()?);
     headers.insert("WM_QOS.CORRELATION_ID", rid.parse()?);
     headers.insert("WM_SEC.TIMESTAMP", timestamp.to_string().parse()?);
 
@@ -173,96 +351,222 @@ impl Client {
         ref client_secret,
         ..
       } => {
-        let access_token = self.get_access_token(false)?;
+        let access_token = access_token.ok_or_else(|| {
+          WalmartError::Msg("access token is required for Token API Authentication".to_string())
+        })?;
         debug!("auth: TokenApi: access_token = {}", access_token);
         headers.insert("WM_SEC.ACCESS_TOKEN", access_token.parse()?);
-        req = req.basic_auth(client_id, Some(client_secret));
+        headers.insert(AUTHORIZATION, basic_auth(client_id, client_secret).parse()?);
       }
     }
-    let req = req.headers(headers);
-    Ok(req)
+    Ok(headers)
   }
 
-  fn clear_access_token(&self) {
-    match self.auth_state {
-      AuthState::TokenApi {
-        ref bearer_token, ..
-      } => {
-        bearer_token.write().unwrap().take();
+  pub fn clear_access_token(&self) {
+    if let AuthState::TokenApi {
+      ref client_id,
+      ref token_store,
+      ..
+    } = self.auth_state
+    {
+      if let Err(err) = token_store.remove(client_id) {
+        warn!("remove access token from token store: {}", err);
       }
-      _ => {}
     }
   }
 
-  fn get_access_token(&self, force_renew: bool) -> WalmartResult<String> {
-    use std::collections::HashMap;
-    #[derive(Debug, Deserialize)]
-    struct WalmartBearerToken {
-      access_token: String,
-      token_type: String,
-      expires_in: u64,
-    }
-
+  /// Returns the stored bearer token if it's not about to expire
+  pub fn get_cached_access_token(&self) -> Option<String> {
     match self.auth_state {
       AuthState::TokenApi {
-        ref bearer_token,
         ref client_id,
-        ref client_secret,
-      } => {
-        if !force_renew {
-          let lock = bearer_token.read().unwrap();
-          if let Some(ref token) = lock.as_ref() {
-            if token.expires_at.saturating_duration_since(Instant::now()) > Duration::from_secs(120)
-            {
-              return Ok(token.access_token.clone());
-            }
+        ref token_store,
+        ..
+      } => match token_store.get(client_id) {
+        Ok(Some(token)) => {
+          if token.expires_at - Utc::now() > chrono::Duration::seconds(120) {
+            Some(token.access_token)
+          } else {
+            None
           }
-          drop(lock);
         }
+        Ok(None) => None,
+        Err(err) => {
+          warn!("read access token from token store: {}", err);
+          None
+        }
+      },
+      _ => None,
+    }
+  }
 
-        let mut form = HashMap::new();
-        form.insert("grant_type", "client_credentials");
+  pub fn get_token_url(&self) -> WalmartResult<Url> {
+    self.base_url.join("/v3/token").map_err(Into::into)
+  }
+
+  pub fn get_token_form(&self) -> Vec<(&'static str, &'static str)> {
+    vec![("grant_type", "client_credentials")]
+  }
 
+  pub fn get_token_request_headers(&self) -> WalmartResult<HeaderMap> {
+    match self.auth_state {
+      AuthState::TokenApi {
+        ref client_id,
+        ref client_secret,
+        ..
+      } => {
         let mut headers = HeaderMap::new();
         let rid: String = thread_rng().gen_ascii_chars().take(10).collect();
-        headers.insert("WM_SVC.NAME", "Walmart Marketplace".parse()?);
+        headers.insert("WM_SVC.NAME", self.service_name.parse()?);
         headers.insert("WM_QOS.CORRELATION_ID", rid.parse()?);
         headers.insert("Accept", "application/json".parse()?);
+        headers.insert(AUTHORIZATION, basic_auth(client_id, client_secret).parse()?);
+        Ok(headers)
+      }
+      _ => Err(WalmartError::Msg(
+        "cannot get bearer with Signature Authentication".to_string(),
+      )),
+    }
+  }
 
-        let mut res = self
-          .http
-          .request(Method::POST, &format!("{}/v3/token", BASE_URL))
-          .headers(headers)
-          .form(&form)
-          .basic_auth(client_id, Some(client_secret))
-          .send()?;
-
-        let token: WalmartBearerToken = res.json()?;
-        let access_token = token.access_token.clone();
-
-        if token.token_type != "Bearer" {
-          return Err(WalmartError::Msg(format!(
-            "unsupported token type: {}",
-            token.token_type
-          )));
-        }
+  /// Stores a token returned by the Token API and returns the access token
+  pub fn set_access_token(&self, token: WalmartBearerToken) -> WalmartResult<String> {
+    if token.token_type != "Bearer" {
+      return Err(WalmartError::Msg(format!(
+        "unsupported token type: {}",
+        token.token_type
+      )));
+    }
 
-        debug!("token: {:#?}", token);
+    debug!("token: {:#?}", token);
 
-        let mut lock = bearer_token.write().unwrap();
-        lock.replace(BearerToken {
+    match self.auth_state {
+      AuthState::TokenApi {
+        ref client_id,
+        ref token_store,
+        ..
+      } => {
+        let stored = StoredToken {
           access_token: token.access_token,
-          expires_at: Instant::now() + Duration::from_secs(token.expires_in),
-        });
-
-        Ok(access_token)
+          expires_at: Utc::now() + chrono::Duration::seconds(token.expires_in as i64),
+        };
+        if let Err(err) = token_store.set(client_id, &stored) {
+          warn!("write access token to token store: {}", err);
+        }
+        Ok(stored.access_token)
       }
-      _ => {
-        return Err(WalmartError::Msg(
-          "cannot get bearer with Signature Authentication".to_string(),
-        ))
+      _ => Err(WalmartError::Msg(
+        "cannot get bearer with Signature Authentication".to_string(),
+      )),
+    }
+  }
+}
+
+fn basic_auth(username: &str, password: &str) -> String {
+  format!(
+    "Basic {}",
+    base64::encode(&format!("{}:{}", username, password))
+  )
+}
+
+/// Where `Client::send_request` gets a request for the next attempt
+enum RequestSource<'a> {
+  /// Clone of a built request, `None` if its body can't be cloned
+  Request(Option<Box<Request>>),
+  /// Builds a new request with fresh authentication headers
+  Builder(&'a dyn Fn() -> WalmartResult<RequestBuilder>),
+}
+
+impl<'a> RequestSource<'a> {
+  fn is_replayable(&self) -> bool {
+    match *self {
+      RequestSource::Request(ref template) => template.is_some(),
+      RequestSource::Builder(_) => true,
+    }
+  }
+
+  fn is_rebuildable(&self) -> bool {
+    match *self {
+      RequestSource::Request(_) => false,
+      RequestSource::Builder(_) => true,
+    }
+  }
+
+  fn build(&self) -> WalmartResult<Request> {
+    match *self {
+      RequestSource::Request(ref template) => template
+        .as_ref()
+        .and_then(|req| req.try_clone())
+        .ok_or_else(|| WalmartError::Msg("request can not be replayed".to_string())),
+      RequestSource::Builder(build) => build()?.build().map_err(Into::into),
+    }
+  }
+}
+
+pub struct Client {
+  state: ClientState,
+  retry_policy: RetryPolicy,
+  rate_limiter: RateLimiter,
+  cassette: Option<Cassette>,
+  http: reqwest::Client,
+}
+
+impl Client {
+  pub fn new(
+    marketplace: WalmartMarketplace,
+    credential: WalmartCredential,
+  ) -> WalmartResult<Client> {
+    ClientBuilder::new(marketplace, credential).build()
+  }
+
+  pub fn with_http_client(
+    marketplace: WalmartMarketplace,
+    credential: WalmartCredential,
+    http: reqwest::Client,
+  ) -> WalmartResult<Client> {
+    ClientBuilder::new(marketplace, credential)
+      .http_client(http)
+      .build()
+  }
+
+  pub fn builder(marketplace: WalmartMarketplace, credential: WalmartCredential) -> ClientBuilder {
+    ClientBuilder::new(marketplace, credential)
+  }
+
+  fn request<P>(&self, method: Method, path: &str, params: P) -> WalmartResult<RequestBuilder>
+  where
+    P: ExtendUrlParams,
+  {
+    let url = self.state.get_url(path, params)?;
+    let access_token = if self.state.use_access_token() {
+      Some(self.get_access_token(false)?)
+    } else {
+      None
+    };
+    let headers =
+      self
+        .state
+        .get_request_headers(&method, &url, access_token.as_ref().map(AsRef::as_ref))?;
+    Ok(self.http.request(method, url.as_str()).headers(headers))
+  }
+
+  fn get_access_token(&self, force_renew: bool) -> WalmartResult<String> {
+    if !force_renew {
+      if let Some(access_token) = self.state.get_cached_access_token() {
+        return Ok(access_token);
       }
     }
+
+    let req = self
+      .http
+      .request(Method::POST, self.state.get_token_url()?)
+      .headers(self.state.get_token_request_headers()?)
+      .form(&self.state.get_token_form())
+      .build()?;
+    let mut res = self.execute(req)?;
+
+    let token: WalmartBearerToken = res.json()?;
+    self.state.set_access_token(token)
   }
 
   pub fn request_json<P>(
@@ -297,20 +601,132 @@ impl Client {
       .map(|req| req.header(ACCEPT, HeaderValue::from_static("application/xml")))
   }
 
+  /// Sends the request, retrying it according to the client's `RetryPolicy`.
+  /// Waits or fails first if the endpoint is out of rate limit tokens.
+  ///
+  /// Retries replay the same request, prefer `send_with` which rebuilds it.
+  #[deprecated(
+    note = "retries replay the signed request without renewing the access token, use `send_with`"
+  )]
   pub fn send(&self, req: RequestBuilder) -> WalmartResult<Response> {
-    match req.send() {
-      Ok(res) => {
-        if res.status() == StatusCode::UNAUTHORIZED {
-          self.clear_access_token();
+    let req = req.build()?;
+    // bodies like multipart readers can't be cloned, those requests are sent only once
+    let template = req.try_clone().map(Box::new);
+    self.send_request(req, RequestSource::Request(template))
+  }
+
+  /// Sends a request built by `build`, which is called again before every retry,
+  /// so each attempt gets a fresh timestamp, signature and access token.
+  ///
+  /// With Token API authentication, a `401 Unauthorized` response renews the
+  /// access token and replays the request once.
+  pub fn send_with<F>(&self, build: F) -> WalmartResult<Response>
+  where
+    F: Fn() -> WalmartResult<RequestBuilder>,
+  {
+    let req = build()?.build()?;
+    self.send_request(req, RequestSource::Builder(&build))
+  }
+
+  fn send_request(&self, mut req: Request, source: RequestSource) -> WalmartResult<Response> {
+    let retry_method = self.retry_policy.should_retry_method(req.method());
+    let mut attempt = 1;
+    let mut reauthenticated = false;
+    loop {
+      let method = req.method().clone();
+      let url = req.url().clone();
+
+      self.rate_limiter.acquire(&method, &url)?;
+
+      let res = self.execute(req);
+      if let Ok(ref res) = res {
+        self.rate_limiter.update(&method, &url, res.headers());
+      }
+
+      let can_retry = source.is_replayable() && attempt < self.retry_policy.max_attempts;
+      let mut renew_access_token = false;
+      let backoff = match res {
+        Ok(ref res) if res.status() == StatusCode::UNAUTHORIZED => {
+          self.state.clear_access_token();
+          if !reauthenticated && self.state.use_access_token() && source.is_rebuildable() {
+            reauthenticated = true;
+            renew_access_token = true;
+            info!(
+              "request unauthorized, renewing access token: method = {}, url = {}",
+              method, url
+            );
+            Some(Duration::from_secs(0))
+          } else {
+            None
+          }
         }
-        Ok(res)
+        Ok(ref res)
+          if can_retry && self.retry_policy.should_retry_status(&method, res.status()) =>
+        {
+          let backoff = self
+            .retry_policy
+            .get_backoff(attempt, get_retry_after(res.headers()));
+          if let Some(backoff) = backoff {
+            warn!(
+              "request failed: method = {}, url = {}, attempt = {}, status = {}, retry in {:?}",
+              method,
+              url,
+              attempt,
+              res.status(),
+              backoff
+            );
+          }
+          backoff
+        }
+        // status errors are not produced by `execute`, so this is a transport error
+        Err(WalmartError::Reqwest(ref err)) if can_retry && retry_method => {
+          let backoff = self.retry_policy.get_backoff(attempt, None);
+          if let Some(backoff) = backoff {
+            warn!(
+              "request failed: method = {}, url = {}, attempt = {}, error = {}, retry in {:?}",
+              method, url, attempt, err, backoff
+            );
+          }
+          backoff
+        }
+        _ => None,
+      };
+
+      match backoff {
+        Some(backoff) => {
+          // replaying with a renewed access token is not a retry
+          if !renew_access_token {
+            attempt += 1;
+          }
+          ::std::thread::sleep(backoff);
+          req = source.build()?;
+        }
+        None => return res,
+      }
+    }
+  }
+
+  /// Sends a request once, or serves it from the cassette
+  fn execute(&self, req: Request) -> WalmartResult<Response> {
+    match self.cassette {
+      Some(ref cassette) if cassette.mode() == CassetteMode::Replay => {
+        cassette.replay_request(&req)
+      }
+      Some(ref cassette) => {
+        let (method, url, headers) = (
+          req.method().clone(),
+          req.url().clone(),
+          req.headers().clone(),
+        );
+        let res = self.http.execute(req)?;
+        cassette.record_response(&method, &url, &headers, res)
       }
-      Err(err) => Err(err.into()),
+      None => self.http.execute(req).map_err(Into::into),
     }
   }
 
   pub fn get_marketplace(&self) -> WalmartMarketplace {
-    self.marketplace
+    self.state.marketplace
   }
 }
 
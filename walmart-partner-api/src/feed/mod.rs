@@ -5,8 +5,16 @@ mod types;
 use serde_urlencoded;
 
 pub use self::types::*;
+#[cfg(feature = "async")]
+use crate::async_client::{body_to_string, AsyncClient, WalmartFuture};
 use crate::client::{Client, Method};
+use crate::paginate::{Page, Paginator};
+#[cfg(feature = "async")]
+use crate::response::parse_json_maybe;
 use crate::xml::Xml;
+use chrono::{DateTime, Utc};
+#[cfg(feature = "async")]
+use futures::Future;
 
 #[derive(Debug, Serialize, Default)]
 #[allow(non_snake_case)]
@@ -16,6 +24,42 @@ pub struct GetAllFeedStatusesQuery<'a> {
   pub offset: Option<i32>,
 }
 
+/// Client side filters of `Client::feed_statuses`
+#[derive(Debug, Default, Clone)]
+pub struct FeedStatusFilter {
+  pub feed_type: Option<String>,
+  pub feed_status: Option<String>,
+  /// Inclusive
+  pub feed_date_from: Option<DateTime<Utc>>,
+  /// Exclusive
+  pub feed_date_to: Option<DateTime<Utc>>,
+}
+
+impl FeedStatusFilter {
+  pub fn matches(&self, status: &FeedStatus) -> bool {
+    self
+      .feed_type
+      .as_ref()
+      .map_or(true, |v| status.feedType.eq_ignore_ascii_case(v))
+      && self
+        .feed_status
+        .as_ref()
+        .map_or(true, |v| status.feedStatus.eq_ignore_ascii_case(v))
+      && self
+        .feed_date_from
+        .map_or(true, |from| status.feedDate >= from)
+      && self.feed_date_to.map_or(true, |to| status.feedDate < to)
+  }
+}
+
+impl Page for FeedStatuses {
+  type Item = FeedStatus;
+
+  fn into_items(self) -> Vec<FeedStatus> {
+    self.results.feed
+  }
+}
+
 #[derive(Debug, Serialize, Default)]
 #[allow(non_snake_case)]
 pub struct GetFeedAndItemStatusQuery {
@@ -31,36 +75,109 @@ impl Client {
   ) -> WalmartResult<FeedStatuses> {
     let qs = serde_urlencoded::to_string(query)?;
     self
-      .send(self.request_json(Method::GET, "/v3/feeds", qs)?)?
+      .send_with(|| self.request_json(Method::GET, "/v3/feeds", qs.clone()))?
       .json_maybe::<FeedStatuses>()
       .map_err(Into::into)
   }
 
+  /// Pages through `/v3/feeds` by `offset`, returning the statuses that match `filter`
+  pub fn feed_statuses<'a>(
+    &'a self,
+    query: &GetAllFeedStatusesQuery,
+    filter: FeedStatusFilter,
+  ) -> Paginator<'a, FeedStatuses> {
+    let feed_id = query.feedId.map(str::to_string);
+    let limit = query.limit;
+    let mut offset = Some(query.offset.unwrap_or(0));
+    Paginator::from_fn(move || {
+      let current = offset.take()?;
+      let res = self.get_all_feed_statuses(&GetAllFeedStatusesQuery {
+        feedId: feed_id.as_deref(),
+        limit,
+        offset: Some(current),
+      });
+      Some(res.map(|mut page| {
+        let next = current + page.results.feed.len() as i32;
+        if !page.results.feed.is_empty() && next < page.totalResults {
+          offset = Some(next);
+        }
+        page.results.feed.retain(|status| filter.matches(status));
+        page
+      }))
+    })
+  }
+
   pub fn get_feed_and_item_status(
     &self,
     feed_id: &str,
     query: &GetFeedAndItemStatusQuery,
   ) -> WalmartResult<PartnerFeedResponse> {
     let path = format!("/v3/feeds/{}", feed_id);
+    let qs = serde_urlencoded::to_string(query)?;
     self
-      .send(self.request_json(Method::GET, &path, serde_urlencoded::to_string(query)?)?)?
+      .send_with(|| self.request_json(Method::GET, &path, qs.clone()))?
       .json_maybe::<PartnerFeedResponse>()
       .map_err(Into::into)
   }
 
+  /// Reads the whole feed first, so the upload can be retried
   pub fn bulk_upload_xml<R: Read + Send + 'static>(
     &self,
     feed_type: &str,
-    feed: R,
+    mut feed: R,
   ) -> WalmartResult<FeedAck> {
-    let form = reqwest::multipart::Form::new()
-        .part("file", reqwest::multipart::Part::reader(feed));
-    let mut res = self.send(
+    let mut bytes = vec![];
+    feed.read_to_end(&mut bytes)?;
+    let mut res = self.send_with(|| {
+      let form = reqwest::multipart::Form::new()
+        .part("file", reqwest::multipart::Part::bytes(bytes.clone()));
       self
-        .request_xml(Method::POST, "/v3/feeds", vec![("feedType", feed_type)])?
-        .multipart(form)
-    )?;
+        .request_xml(Method::POST, "/v3/feeds", vec![("feedType", feed_type)])
+        .map(|req| req.multipart(form))
+    })?;
     let xml = Xml::<FeedAck>::from_res(&mut res)?;
     Ok(xml.into_inner())
   }
 }
+
+#[cfg(feature = "async")]
+impl AsyncClient {
+  pub fn get_all_feed_statuses(
+    &self,
+    query: &GetAllFeedStatusesQuery,
+  ) -> WalmartFuture<FeedStatuses> {
+    let qs = try_future!(serde_urlencoded::to_string(query));
+    self.send_parse(
+      self.request_json(Method::GET, "/v3/feeds", qs),
+      |status, body| parse_json_maybe(status, body_to_string(status, body)?).map_err(Into::into),
+    )
+  }
+
+  pub fn get_feed_and_item_status(
+    &self,
+    feed_id: &str,
+    query: &GetFeedAndItemStatusQuery,
+  ) -> WalmartFuture<PartnerFeedResponse> {
+    let path = format!("/v3/feeds/{}", feed_id);
+    let qs = try_future!(serde_urlencoded::to_string(query));
+    self.send_parse(self.request_json(Method::GET, &path, qs), |status, body| {
+      parse_json_maybe(status, body_to_string(status, body)?).map_err(Into::into)
+    })
+  }
+
+  pub fn bulk_upload_xml(&self, feed_type: &str, feed: Vec<u8>) -> WalmartFuture<FeedAck> {
+    use reqwest::r#async::multipart::{Form, Part};
+
+    self.send_parse(
+      Box::new(
+        self
+          .request_xml(Method::POST, "/v3/feeds", vec![("feedType", feed_type)])
+          .map(move |req| req.multipart(Form::new().part("file", Part::bytes(feed)))),
+      ),
+      |status, body| {
+        let xml = Xml::<FeedAck>::from_text(status, body_to_string(status, body)?)?;
+        Ok(xml.into_inner())
+      },
+    )
+  }
+}
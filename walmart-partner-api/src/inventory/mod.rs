@@ -3,13 +3,19 @@ use crate::result::*;
 mod types;
 
 pub use self::types::*;
+#[cfg(feature = "async")]
+use crate::async_client::{body_to_string, AsyncClient, WalmartFuture};
 use crate::client::{Client, Method};
+#[cfg(feature = "async")]
+use crate::response::parse_json_maybe;
+#[cfg(feature = "async")]
+use futures::Future;
 
 impl Client {
   pub fn get_item_inventory(&self, sku: &str) -> WalmartResult<Inventory> {
     let path = "/v3/inventory";
     self
-      .send(self.request_json(Method::GET, path, vec![("sku", sku)])?)?
+      .send_with(|| self.request_json(Method::GET, path, vec![("sku", sku)]))?
       .json_maybe::<Inventory>()
       .map_err(Into::into)
   }
@@ -17,12 +23,33 @@ impl Client {
   pub fn update_item_inventory(&self, inventory: &Inventory) -> WalmartResult<Inventory> {
     let path = "/v3/inventory";
     self
-      .send(
+      .send_with(|| {
         self
-          .request_json(Method::PUT, path, vec![("sku", &inventory.sku)])?
-          .json(inventory),
-      )?
+          .request_json(Method::PUT, path, vec![("sku", &inventory.sku)])
+          .map(|req| req.json(inventory))
+      })?
       .json_maybe::<Inventory>()
       .map_err(Into::into)
   }
 }
+
+#[cfg(feature = "async")]
+impl AsyncClient {
+  pub fn get_item_inventory(&self, sku: &str) -> WalmartFuture<Inventory> {
+    let path = "/v3/inventory";
+    self.send_parse(
+      self.request_json(Method::GET, path, vec![("sku", sku)]),
+      |status, body| parse_json_maybe(status, body_to_string(status, body)?).map_err(Into::into),
+    )
+  }
+
+  pub fn update_item_inventory(&self, inventory: &Inventory) -> WalmartFuture<Inventory> {
+    let path = "/v3/inventory";
+    let req = self.request_json(Method::PUT, path, vec![("sku", &inventory.sku)]);
+    let inventory = inventory.clone();
+    self.send_parse(
+      Box::new(req.map(move |req| req.json(&inventory))),
+      |status, body| parse_json_maybe(status, body_to_string(status, body)?).map_err(Into::into),
+    )
+  }
+}
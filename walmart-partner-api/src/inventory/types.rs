@@ -1,10 +1,10 @@
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Quantity {
   pub unit: String,
   pub amount: i32,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(non_snake_case)]
 pub struct Inventory {
   pub sku: String,
@@ -23,4 +23,4 @@ impl Inventory {
       fulfillmentLagTime: Some(fulfillment_lag_time),
     }
   }
-}
\ No newline at end of file
+}
@@ -1,6 +1,9 @@
 use crate::result::*;
 mod types;
+#[cfg(feature = "async")]
+use crate::async_client::{body_to_string, AsyncClient, WalmartFuture};
 use crate::client::{Client, Method};
+use crate::paginate::Paginator;
 use crate::xml::Xml;
 
 pub use self::types::*;
@@ -32,10 +35,48 @@ impl Client {
     params: &GetAllItemsQueryParams,
   ) -> WalmartResult<(Xml<GetAllItems>, Option<GetAllItemsQueryParams>)> {
     let qs = serde_urlencoded::to_string(params)?;
-    let mut res = self.send(self.request_xml(Method::GET, "/v3/items", qs)?)?;
+    let mut res = self.send_with(|| self.request_xml(Method::GET, "/v3/items", qs.clone()))?;
 
     let xml = Xml::<GetAllItems>::from_res(&mut res)?;
     let next_params = xml.get_next_query_params(params, self.get_marketplace());
     Ok((xml, next_params))
   }
+
+  /// Iterates over all items, by `nextCursor` in the USA and by `offset` in Canada
+  pub fn items<'a>(&'a self, params: &GetAllItemsQueryParams) -> Paginator<'a, GetAllItems> {
+    let mut params = Some(params.clone());
+    let mut fetched = 0;
+    Paginator::from_fn(move || {
+      let current = params.take()?;
+      Some(self.get_all_items(&current).map(|(xml, next_params)| {
+        let page = xml.into_inner();
+        fetched += page.items.len() as i64;
+        // guards against a cursor that never ends
+        if page.totalItems == 0 || fetched < page.totalItems {
+          params = next_params;
+        }
+        page
+      }))
+    })
+  }
+}
+
+#[cfg(feature = "async")]
+impl AsyncClient {
+  pub fn get_all_items(
+    &self,
+    params: &GetAllItemsQueryParams,
+  ) -> WalmartFuture<(Xml<GetAllItems>, Option<GetAllItemsQueryParams>)> {
+    let qs = try_future!(serde_urlencoded::to_string(params));
+    let params = params.clone();
+    let marketplace = self.get_marketplace();
+    self.send_parse(
+      self.request_xml(Method::GET, "/v3/items", qs),
+      move |status, body| {
+        let xml = Xml::<GetAllItems>::from_text(status, body_to_string(status, body)?)?;
+        let next_params = xml.get_next_query_params(&params, marketplace);
+        Ok((xml, next_params))
+      },
+    )
+  }
 }
@@ -1,7 +1,11 @@
 use super::GetAllItemsQueryParams;
 use crate::client::WalmartMarketplace;
+use crate::paginate::Page;
 use crate::result::*;
 use crate::xml::*;
+use crate::Money;
+use bigdecimal::BigDecimal;
+use std::str::FromStr;
 use xmltree::Element;
 
 /// Response of `get_all_items`
@@ -10,35 +14,67 @@ use xmltree::Element;
 pub struct GetAllItems {
   pub items: Vec<Item>,
 
-  /// US only
+  /// `0` if not returned
   pub totalItems: i64,
   /// US only
   pub nextCursor: Option<String>,
 }
 
+impl Page for GetAllItems {
+  type Item = Item;
+
+  fn into_items(self) -> Vec<Item> {
+    self.items
+  }
+}
+
 impl GetAllItems {
+  /// Returns the query parameters of the next page, `None` if this is the last page.
+  ///
+  /// USA pages by `nextCursor`, Canada by `offset` and `limit`.
   pub(crate) fn get_next_query_params(
     &self,
     current_params: &GetAllItemsQueryParams,
-    _marketplace: WalmartMarketplace,
+    marketplace: WalmartMarketplace,
   ) -> Option<GetAllItemsQueryParams> {
-    self
-      .nextCursor
-      .as_ref()
-      .map(|next_cursor| GetAllItemsQueryParams {
-        nextCursor: next_cursor.to_string(),
-        ..current_params.clone()
-      })
+    if self.items.is_empty() {
+      return None;
+    }
+
+    match marketplace {
+      WalmartMarketplace::USA => self
+        .nextCursor
+        .as_ref()
+        .filter(|next_cursor| !next_cursor.is_empty())
+        .map(|next_cursor| GetAllItemsQueryParams {
+          nextCursor: next_cursor.to_string(),
+          ..current_params.clone()
+        }),
+      WalmartMarketplace::Canada => {
+        let offset = current_params.offset.unwrap_or(0) + self.items.len() as i32;
+        let is_last_page = if self.totalItems > 0 {
+          i64::from(offset) >= self.totalItems
+        } else {
+          // a short page is the last one if `totalItems` is missing
+          current_params
+            .limit
+            .map(|limit| (self.items.len() as i32) < limit)
+            .unwrap_or(false)
+        };
+        if is_last_page {
+          None
+        } else {
+          Some(GetAllItemsQueryParams {
+            offset: Some(offset),
+            ..current_params.clone()
+          })
+        }
+      }
+    }
   }
 }
 
 #[derive(Debug, Serialize, Default)]
-pub struct Price {
-  pub currency: String,
-  pub amount: String,
-}
-
-#[derive(Debug, Serialize)]
 #[allow(non_snake_case)]
 pub struct Item {
   pub mart: String,
@@ -49,7 +85,8 @@ pub struct Item {
   pub productName: String,
   pub shelf: String,
   pub productType: String,
-  pub price: Price,
+  /// `None` if not returned or malformed
+  pub price: Option<Money>,
   pub publishedStatus: String,
 }
 
@@ -58,31 +95,20 @@ impl FromXmlElement for GetAllItems {
     let items = elem
       .children
       .iter()
-      .filter_map(|c| {
-        if c.name == "ItemResponse" {
-          Some(Item {
-            mart: c.get_child_text_or_default("mart"),
-            sku: c.get_child_text_or_default("sku"),
-            wpid: c.get_child_text_or_default("wpid"),
-            upc: c.get_child_text_or_default("upc"),
-            gtin: c.get_child_text_or_default("gtin"),
-            productName: c.get_child_text_or_default("productName"),
-            shelf: c.get_child_text_or_default("shelf"),
-            productType: c.get_child_text_or_default("productType"),
-            price: c
-              .get_child("price")
-              .map(|c| Price {
-                currency: c.get_child_text_or_default("currency"),
-                amount: c.get_child_text_or_default("amount"),
-              })
-              .unwrap_or_default(),
-            publishedStatus: c.get_child_text_or_default("publishedStatus"),
-          })
-        } else {
-          None
-        }
+      .filter(|c| c.name == "ItemResponse")
+      .map(|c| Item {
+        mart: c.get_child_text_or_default("mart"),
+        sku: c.get_child_text_or_default("sku"),
+        wpid: c.get_child_text_or_default("wpid"),
+        upc: c.get_child_text_or_default("upc"),
+        gtin: c.get_child_text_or_default("gtin"),
+        productName: c.get_child_text_or_default("productName"),
+        shelf: c.get_child_text_or_default("shelf"),
+        productType: c.get_child_text_or_default("productType"),
+        price: c.get_child("price").and_then(parse_price),
+        publishedStatus: c.get_child_text_or_default("publishedStatus"),
       })
-      .collect();
+      .collect::<Vec<_>>();
     Ok(GetAllItems {
       items,
       totalItems: elem
@@ -94,3 +120,87 @@ impl FromXmlElement for GetAllItems {
     })
   }
 }
+
+fn parse_price(elem: &Element) -> Option<Money> {
+  let amount = elem.get_child_text_or_default("amount");
+  if amount.is_empty() {
+    return None;
+  }
+  match BigDecimal::from_str(&amount) {
+    Ok(amount) => Some(Money {
+      currency: elem.get_child_text_or_default("currency"),
+      amount,
+    }),
+    Err(_) => {
+      warn!("invalid item price amount: {}", amount);
+      None
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn page(len: usize, total_items: i64, next_cursor: Option<&str>) -> GetAllItems {
+    GetAllItems {
+      items: (0..len).map(|_| Item::default()).collect(),
+      totalItems: total_items,
+      nextCursor: next_cursor.map(str::to_string),
+    }
+  }
+
+  #[test]
+  fn test_malformed_price() {
+    let xml = r#"<ItemResponses>
+      <ItemResponse><sku>A</sku><price><currency>USD</currency><amount>1.5</amount></price></ItemResponse>
+      <ItemResponse><sku>B</sku><price><currency>USD</currency><amount>N/A</amount></price></ItemResponse>
+    </ItemResponses>"#;
+    let page = GetAllItems::from_xml_element(Element::parse(xml.as_bytes()).unwrap()).unwrap();
+    assert_eq!(page.items.len(), 2);
+    assert_eq!(page.items[0].price.as_ref().unwrap().to_string(), "1.5 USD");
+    assert_eq!(page.items[1].price, None);
+  }
+
+  #[test]
+  fn test_next_query_params_canada() {
+    let params = GetAllItemsQueryParams {
+      limit: Some(2),
+      ..Default::default()
+    };
+    let next = page(2, 5, None)
+      .get_next_query_params(&params, WalmartMarketplace::Canada)
+      .unwrap();
+    assert_eq!(next.offset, Some(2));
+    let next = page(2, 5, None)
+      .get_next_query_params(&next, WalmartMarketplace::Canada)
+      .unwrap();
+    assert_eq!(next.offset, Some(4));
+    assert!(page(1, 5, None)
+      .get_next_query_params(&next, WalmartMarketplace::Canada)
+      .is_none());
+
+    // without `totalItems`
+    assert!(page(2, 0, None)
+      .get_next_query_params(&params, WalmartMarketplace::Canada)
+      .is_some());
+    assert!(page(1, 0, None)
+      .get_next_query_params(&params, WalmartMarketplace::Canada)
+      .is_none());
+  }
+
+  #[test]
+  fn test_next_query_params_usa() {
+    let params = GetAllItemsQueryParams::default();
+    let next = page(2, 5, Some("cursor"))
+      .get_next_query_params(&params, WalmartMarketplace::USA)
+      .unwrap();
+    assert_eq!(next.nextCursor, "cursor");
+    assert!(page(2, 5, Some(""))
+      .get_next_query_params(&params, WalmartMarketplace::USA)
+      .is_none());
+    assert!(page(0, 5, Some("cursor"))
+      .get_next_query_params(&params, WalmartMarketplace::USA)
+      .is_none());
+  }
+}
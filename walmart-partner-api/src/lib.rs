@@ -3,6 +3,7 @@ extern crate bigdecimal;
 extern crate chrono;
 extern crate csv;
 extern crate failure;
+extern crate http;
 #[macro_use]
 extern crate failure_derive;
 #[macro_use]
@@ -11,17 +12,37 @@ extern crate serde_derive;
 extern crate serde_json;
 #[macro_use]
 extern crate log;
+#[cfg(feature = "async")]
+extern crate futures;
 
+#[cfg(feature = "async")]
+#[macro_use]
+mod async_client;
+mod cassette;
 mod client;
 pub mod feed;
 pub mod inventory;
 pub mod item;
+mod money;
 pub mod order;
+pub mod paginate;
+mod rate_limit;
 pub mod report;
 pub mod response;
 pub mod result;
+mod retry;
+pub mod returns;
 mod sign;
+mod token_store;
 mod utils;
 mod xml;
 
-pub use self::client::{Client, WalmartCredential, WalmartMarketplace};
+#[cfg(feature = "async")]
+pub use self::async_client::{AsyncClient, WalmartFuture};
+pub use self::cassette::{Cassette, CassetteMode, Interaction, RecordedRequest, RecordedResponse};
+pub use self::client::{Client, ClientBuilder, WalmartCredential, WalmartMarketplace};
+pub use self::money::Money;
+pub use self::rate_limit::RateLimitMode;
+pub use self::retry::RetryPolicy;
+pub use self::sign::Signature;
+pub use self::token_store::{FileTokenStore, MemoryTokenStore, StoredToken, TokenStore};
@@ -0,0 +1,114 @@
+use crate::result::*;
+use bigdecimal::BigDecimal;
+use serde::de::{self, Deserializer};
+use serde::ser::{self, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// An exact amount of money
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Money {
+  pub currency: String,
+  /// Serialized as a JSON number, decimal strings are accepted too
+  #[serde(
+    deserialize_with = "deserialize_amount",
+    serialize_with = "serialize_amount"
+  )]
+  pub amount: BigDecimal,
+}
+
+impl Money {
+  pub fn new(currency: &str, amount: BigDecimal) -> Self {
+    Money {
+      currency: currency.to_string(),
+      amount,
+    }
+  }
+}
+
+impl fmt::Display for Money {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{} {}", self.amount, self.currency)
+  }
+}
+
+impl FromStr for Money {
+  type Err = WalmartError;
+
+  /// Parses the `Display` format `39.99 USD`, the currency is optional
+  fn from_str(value: &str) -> WalmartResult<Self> {
+    let mut parts = value.split_whitespace();
+    let amount = parts.next().unwrap_or_default();
+    let amount = BigDecimal::from_str(amount)
+      .map_err(|_| -> WalmartError { format!("invalid amount `{}`", value).into() })?;
+    Ok(Money {
+      currency: parts.next().unwrap_or_default().to_string(),
+      amount,
+    })
+  }
+}
+
+/// Walmart returns amounts as JSON numbers, `f64` values are converted by
+/// their shortest representation so `39.99` stays `39.99`
+struct AmountVisitor;
+impl<'de> de::Visitor<'de> for AmountVisitor {
+  type Value = BigDecimal;
+
+  fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    formatter.write_str("a number or a decimal string like `39.99`")
+  }
+
+  fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+    Ok(BigDecimal::from(v))
+  }
+
+  fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+    Ok(BigDecimal::from(v))
+  }
+
+  fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+    self.visit_str(&v.to_string())
+  }
+
+  fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+    BigDecimal::from_str(v.trim()).map_err(|_| E::custom(format!("invalid decimal value `{}`", v)))
+  }
+}
+
+pub fn deserialize_amount<'de, D>(d: D) -> Result<BigDecimal, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  d.deserialize_any(AmountVisitor)
+}
+
+pub fn serialize_amount<S>(amount: &BigDecimal, s: S) -> Result<S::Ok, S::Error>
+where
+  S: Serializer,
+{
+  serde_json::Number::from_str(&amount.to_string())
+    .map_err(ser::Error::custom)?
+    .serialize(s)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_money_serde() {
+    let money: Money = serde_json::from_str(r#"{"currency":"USD","amount":39.99}"#).unwrap();
+    assert_eq!(money.amount, BigDecimal::from_str("39.99").unwrap());
+    let total = &money.amount + &money.amount + &money.amount;
+    assert_eq!(total, BigDecimal::from_str("119.97").unwrap());
+    assert_eq!(
+      serde_json::to_string(&money).unwrap(),
+      r#"{"currency":"USD","amount":39.99}"#
+    );
+
+    let money: Money = serde_json::from_str(r#"{"currency":"CAD","amount":"10"}"#).unwrap();
+    assert_eq!(money.to_string(), "10 CAD");
+    assert_eq!(money.to_string().parse::<Money>().unwrap(), money);
+    assert!("abc USD".parse::<Money>().is_err());
+  }
+}
@@ -0,0 +1,172 @@
+//! Tracking pages and tracking number formats of known carriers
+
+use super::enums::Carrier;
+use crate::result::*;
+
+/// Tracking page with `{}` replaced by the tracking number, and the number check
+type Tracking = (&'static str, fn(&str) -> bool);
+
+fn get_tracking(carrier: &Carrier) -> Option<Tracking> {
+  let tracking: Tracking = match *carrier {
+    Carrier::UPS => ("https://www.ups.com/track?tracknum={}", is_ups),
+    Carrier::USPS => (
+      "https://tools.usps.com/go/TrackConfirmAction?tLabels={}",
+      is_usps,
+    ),
+    Carrier::FedEx => ("https://www.fedex.com/fedextrack/?trknbr={}", is_fedex),
+    Carrier::DHL => (
+      "https://www.dhl.com/en/express/tracking.html?AWB={}",
+      is_dhl,
+    ),
+    Carrier::OnTrac => ("https://www.ontrac.com/tracking/?number={}", is_ontrac),
+    Carrier::LaserShip => ("https://www.lasership.com/track/{}", is_lasership),
+    Carrier::CanadaPost => (
+      "https://www.canadapost-postescanada.ca/track-reperez/en#/search?searchFor={}",
+      is_canada_post,
+    ),
+    Carrier::Purolator => (
+      "https://www.purolator.com/en/shipping/tracker?pin={}",
+      is_purolator,
+    ),
+    Carrier::Other(_) => return None,
+  };
+  Some(tracking)
+}
+
+impl Carrier {
+  /// Tracking page of `tracking_number`, `None` for carriers unknown to this crate
+  pub fn get_tracking_url(&self, tracking_number: &str) -> Option<String> {
+    get_tracking(self).map(|(url, _)| url.replace("{}", tracking_number.trim()))
+  }
+
+  /// Whether `tracking_number` has a format used by the carrier, always `true` for unknown carriers
+  pub fn is_valid_tracking_number(&self, tracking_number: &str) -> bool {
+    let value = tracking_number.trim().to_ascii_uppercase();
+    get_tracking(self).map_or(true, |(_, is_valid)| value.is_ascii() && is_valid(&value))
+  }
+}
+
+/// Checks the tracking number of a shipment to `carrier_name`
+pub fn validate_tracking_number(
+  carrier_name: Option<&str>,
+  tracking_number: &str,
+) -> WalmartResult<()> {
+  if tracking_number.trim().is_empty() {
+    return Err("tracking number is empty".to_string().into());
+  }
+  match carrier_name.map(Carrier::from) {
+    Some(ref carrier) if !carrier.is_valid_tracking_number(tracking_number) => Err(
+      format!(
+        "'{}' is not a valid {} tracking number",
+        tracking_number, carrier
+      )
+      .into(),
+    ),
+    _ => Ok(()),
+  }
+}
+
+/// `tracking_url` if not empty, otherwise derived from the carrier, empty for unknown carriers
+pub fn get_tracking_url(
+  carrier_name: Option<&str>,
+  tracking_number: &str,
+  tracking_url: &str,
+) -> String {
+  if !tracking_url.is_empty() {
+    return tracking_url.to_string();
+  }
+  carrier_name
+    .and_then(|name| Carrier::from(name).get_tracking_url(tracking_number))
+    .unwrap_or_default()
+}
+
+fn is_digits(value: &str, lens: &[usize]) -> bool {
+  lens.contains(&value.len()) && value.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn is_alphanumeric(value: &str) -> bool {
+  value.bytes().all(|b| b.is_ascii_alphanumeric())
+}
+
+/// `prefix_len` letters, `digits` digits and `suffix`, e.g. the international `EA123456789US`
+fn is_upu(value: &str, prefix_len: usize, digits: usize, suffix: &str) -> bool {
+  value.len() == prefix_len + digits + suffix.len()
+    && value.ends_with(suffix)
+    && value[..prefix_len].bytes().all(|b| b.is_ascii_alphabetic())
+    && is_digits(&value[prefix_len..prefix_len + digits], &[digits])
+}
+
+fn is_ups(value: &str) -> bool {
+  (value.len() == 18 && value.starts_with("1Z") && is_alphanumeric(value))
+    || (value.starts_with('T') && is_digits(&value[1..], &[10]))
+    || is_digits(value, &[9, 26])
+}
+
+fn is_usps(value: &str) -> bool {
+  is_digits(value, &[20, 22, 26, 30, 34]) || is_upu(value, 2, 9, "US")
+}
+
+fn is_fedex(value: &str) -> bool {
+  is_digits(value, &[12, 15, 20, 22, 34])
+}
+
+fn is_dhl(value: &str) -> bool {
+  is_digits(value, &[10, 11])
+    || (value.starts_with("JJD") && is_digits(&value[3..], &[10, 11, 12, 13, 14, 15, 16, 17, 18]))
+    || (value.starts_with("GM") && is_digits(&value[2..], &[16, 17, 18, 19, 20, 21, 22]))
+}
+
+fn is_ontrac(value: &str) -> bool {
+  (value.starts_with('C') || value.starts_with('D')) && is_digits(&value[1..], &[14])
+}
+
+fn is_lasership(value: &str) -> bool {
+  (value.starts_with("1LS") && (10..=24).contains(&value.len()) && is_alphanumeric(value))
+    || is_upu(value, 3, 8, "")
+}
+
+fn is_canada_post(value: &str) -> bool {
+  is_digits(value, &[12, 16]) || is_upu(value, 2, 9, "CA")
+}
+
+fn is_purolator(value: &str) -> bool {
+  is_digits(value, &[12]) || is_upu(value, 3, 9, "")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_tracking() {
+    assert_eq!(
+      Carrier::UPS.get_tracking_url("1Z999AA10123456784"),
+      Some("https://www.ups.com/track?tracknum=1Z999AA10123456784".to_string())
+    );
+    assert!(Carrier::UPS.is_valid_tracking_number("1z999aa10123456784"));
+    assert!(!Carrier::UPS.is_valid_tracking_number("123"));
+    assert!(Carrier::USPS.is_valid_tracking_number("9400111899223100000000"));
+    assert!(Carrier::USPS.is_valid_tracking_number("EA123456789US"));
+    assert!(Carrier::FedEx.is_valid_tracking_number("123456789012"));
+    assert!(!Carrier::FedEx.is_valid_tracking_number("1Z999AA10123456784"));
+    assert!(Carrier::OnTrac.is_valid_tracking_number("C11031500001879"));
+    assert!(Carrier::CanadaPost.is_valid_tracking_number("1234567890123456"));
+    assert!(Carrier::Purolator.is_valid_tracking_number("ABC123456789"));
+
+    let other = Carrier::from("Some Courier");
+    assert!(other.is_valid_tracking_number("anything"));
+    assert_eq!(other.get_tracking_url("anything"), None);
+
+    assert!(validate_tracking_number(Some("UPS"), "123").is_err());
+    assert!(validate_tracking_number(None, " ").is_err());
+    assert_eq!(
+      get_tracking_url(Some("FedEx"), "123456789012", ""),
+      "https://www.fedex.com/fedextrack/?trknbr=123456789012"
+    );
+    assert_eq!(
+      get_tracking_url(Some("FedEx"), "123456789012", "https://example.com"),
+      "https://example.com"
+    );
+    assert_eq!(get_tracking_url(Some("Some Courier"), "123", ""), "");
+  }
+}
@@ -0,0 +1,168 @@
+use serde::de::{Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+use std::fmt;
+
+/// Defines an enum of the string values Walmart documents, values unknown
+/// to this crate are kept in `Other` so they don't fail deserialization.
+macro_rules! string_enum {
+  (
+    $(#[$meta:meta])*
+    pub enum $name:ident {
+      $($variant:ident => $value:expr,)*
+    }
+  ) => {
+    $(#[$meta])*
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    pub enum $name {
+      $($variant,)*
+      Other(String),
+    }
+
+    impl $name {
+      pub fn as_str(&self) -> &str {
+        match *self {
+          $($name::$variant => $value,)*
+          $name::Other(ref value) => value,
+        }
+      }
+    }
+
+    impl<'a> From<&'a str> for $name {
+      /// Known values are matched exactly, so `Other` serializes back unchanged
+      fn from(value: &'a str) -> Self {
+        $(
+          if value == $value {
+            return $name::$variant;
+          }
+        )*
+        $name::Other(value.to_string())
+      }
+    }
+
+    impl From<String> for $name {
+      fn from(value: String) -> Self {
+        match $name::from(value.as_str()) {
+          $name::Other(_) => $name::Other(value),
+          known => known,
+        }
+      }
+    }
+
+    impl fmt::Display for $name {
+      fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+      }
+    }
+
+    impl Serialize for $name {
+      fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+      }
+    }
+
+    impl<'de> Deserialize<'de> for $name {
+      fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map($name::from)
+      }
+    }
+  };
+}
+
+string_enum! {
+  /// `OrderLineStatus::status`
+  pub enum OrderLineStatusKind {
+    Created => "Created",
+    Acknowledged => "Acknowledged",
+    Shipped => "Shipped",
+    Delivered => "Delivered",
+    Cancelled => "Cancelled",
+  }
+}
+
+string_enum! {
+  /// `OrderLineChargeItem::chargeType`
+  pub enum ChargeType {
+    Product => "PRODUCT",
+    Shipping => "SHIPPING",
+  }
+}
+
+string_enum! {
+  /// `Quantity::unitOfMeasurement`
+  pub enum UnitOfMeasurement {
+    Each => "EACH",
+  }
+}
+
+string_enum! {
+  /// `OrderLineTrackingInfoCarrier::carrier`
+  #[allow(clippy::upper_case_acronyms)]
+  pub enum Carrier {
+    UPS => "UPS",
+    USPS => "USPS",
+    FedEx => "FedEx",
+    DHL => "DHL",
+    OnTrac => "OnTrac",
+    LaserShip => "LaserShip",
+    CanadaPost => "Canada Post",
+    Purolator => "Purolator",
+  }
+}
+
+string_enum! {
+  /// `ShipNode::nodeType` and `QueryParams::shipNodeType`
+  pub enum ShipNodeType {
+    SellerFulfilled => "SellerFulfilled",
+    WFSFulfilled => "WFSFulfilled",
+    ThreePLFulfilled => "3PLFulfilled",
+  }
+}
+
+string_enum! {
+  /// Reason of `Client::cancel_order_lines`
+  pub enum CancellationReason {
+    CustomerRequestedSellerToCancel => "CUSTOMER_REQUESTED_SELLER_TO_CANCEL",
+    CancelBySeller => "CANCEL_BY_SELLER",
+    SellerCancelOutOfStock => "SELLER_CANCEL_OUT_OF_STOCK",
+    SellerCancelPricingError => "SELLER_CANCEL_PRICING_ERROR",
+    SellerCancelNonDeliverableToAddress => "SELLER_CANCEL_NON_DELIVERABLE_TO_ADDRESS",
+    SellerCancelFraudStopShipment => "SELLER_CANCEL_FRAUD_STOP_SHIPMENT",
+  }
+}
+
+string_enum! {
+  /// Reason of `Client::refund_order_lines`
+  pub enum RefundReason {
+    BillingError => "BillingError",
+    TaxExemptCustomer => "TaxExemptCustomer",
+    ItemNotAsAdvertised => "ItemNotAsAdvertised",
+    IncorrectItemReceived => "IncorrectItemReceived",
+    CancelledYetShipped => "CancelledYetShipped",
+    ItemNotReceivedByCustomer => "ItemNotReceivedByCustomer",
+    IncorrectShippingPrice => "IncorrectShippingPrice",
+    DamagedItem => "DamagedItem",
+    DefectiveItem => "DefectiveItem",
+    CustomerChangedMind => "CustomerChangedMind",
+    CustomerReceivedItemLate => "CustomerReceivedItemLate",
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_string_enum() {
+    let status: OrderLineStatusKind = serde_json::from_str(r#""Shipped""#).unwrap();
+    assert_eq!(status, OrderLineStatusKind::Shipped);
+    let carrier: Carrier = serde_json::from_str(r#""FedEx""#).unwrap();
+    assert_eq!(carrier, Carrier::FedEx);
+    let carrier: Carrier = serde_json::from_str(r#""FEDEX""#).unwrap();
+    assert_eq!(carrier, Carrier::Other("FEDEX".to_string()));
+    assert_eq!(serde_json::to_string(&carrier).unwrap(), r#""FEDEX""#);
+
+    let status: OrderLineStatusKind = serde_json::from_str(r#""Refunded""#).unwrap();
+    assert_eq!(status, OrderLineStatusKind::Other("Refunded".to_string()));
+    assert_eq!(serde_json::to_string(&status).unwrap(), r#""Refunded""#);
+  }
+}
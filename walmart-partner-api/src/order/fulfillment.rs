@@ -0,0 +1,238 @@
+//! Per line quantities aggregated from `orderLineStatuses`
+
+use super::enums::OrderLineStatusKind;
+use super::types::*;
+use crate::result::*;
+
+/// Units of an order line by status
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LineQuantities {
+  pub ordered: u32,
+  pub created: u32,
+  pub acknowledged: u32,
+  pub shipped: u32,
+  pub delivered: u32,
+  pub cancelled: u32,
+  /// Units in statuses unknown to this crate
+  pub other: u32,
+}
+
+impl Quantity {
+  /// Parses `amount`, Walmart sends whole units such as `"1"` or `"1.0"`
+  pub fn get_amount(&self) -> WalmartResult<u32> {
+    let amount = self.amount.trim();
+    amount
+      .parse::<u32>()
+      .ok()
+      .or_else(|| {
+        amount
+          .parse::<f64>()
+          .ok()
+          .filter(|v| *v >= 0.0 && v.fract() == 0.0)
+          .map(|v| v as u32)
+      })
+      .ok_or_else(|| format!("invalid quantity amount: {}", self.amount).into())
+  }
+}
+
+impl OrderLine {
+  pub fn get_quantities(&self) -> WalmartResult<LineQuantities> {
+    let mut quantities = LineQuantities {
+      ordered: self.orderLineQuantity.get_amount()?,
+      ..Default::default()
+    };
+    for status in &self.orderLineStatuses.orderLineStatus {
+      let amount = status.statusQuantity.get_amount()?;
+      let total = match status.status {
+        OrderLineStatusKind::Created => &mut quantities.created,
+        OrderLineStatusKind::Acknowledged => &mut quantities.acknowledged,
+        OrderLineStatusKind::Shipped => &mut quantities.shipped,
+        OrderLineStatusKind::Delivered => &mut quantities.delivered,
+        OrderLineStatusKind::Cancelled => &mut quantities.cancelled,
+        OrderLineStatusKind::Other(_) => &mut quantities.other,
+      };
+      // amounts come from Walmart, so don't trust them not to overflow
+      *total = total.saturating_add(amount);
+    }
+    Ok(quantities)
+  }
+
+  /// Units neither shipped, delivered nor cancelled
+  pub fn remaining_to_ship(&self) -> WalmartResult<u32> {
+    let q = self.get_quantities()?;
+    Ok(
+      q.ordered.saturating_sub(
+        q.shipped
+          .saturating_add(q.delivered)
+          .saturating_add(q.cancelled),
+      ),
+    )
+  }
+
+  /// At least one unit was shipped and none remain, cancelled units are ignored
+  pub fn is_fully_shipped(&self) -> WalmartResult<bool> {
+    let q = self.get_quantities()?;
+    Ok((q.shipped > 0 || q.delivered > 0) && self.remaining_to_ship()? == 0)
+  }
+
+  pub fn is_cancelled(&self) -> WalmartResult<bool> {
+    let q = self.get_quantities()?;
+    Ok(q.cancelled > 0 && q.cancelled >= q.ordered)
+  }
+
+  /// Tracking of shipped and delivered units
+  pub fn shipped_tracking(&self) -> Vec<&OrderLineTrackingInfo> {
+    self
+      .orderLineStatuses
+      .orderLineStatus
+      .iter()
+      .filter(|status| {
+        matches!(
+          status.status,
+          OrderLineStatusKind::Shipped | OrderLineStatusKind::Delivered
+        )
+      })
+      .filter_map(|status| status.trackingInfo.as_ref())
+      .collect()
+  }
+}
+
+impl OrderLines {
+  pub fn get_line(&self, line_number: &str) -> Option<&OrderLine> {
+    self
+      .orderLine
+      .iter()
+      .find(|line| line.lineNumber == line_number)
+  }
+
+  pub fn remaining_to_ship(&self) -> WalmartResult<u32> {
+    self.orderLine.iter().try_fold(0u32, |total, line| {
+      Ok(total.saturating_add(line.remaining_to_ship()?))
+    })
+  }
+
+  /// Every line is shipped or cancelled, and at least one is shipped
+  pub fn is_fully_shipped(&self) -> WalmartResult<bool> {
+    let mut any_shipped = false;
+    for line in &self.orderLine {
+      if line.is_fully_shipped()? {
+        any_shipped = true;
+      } else if !line.is_cancelled()? {
+        return Ok(false);
+      }
+    }
+    Ok(any_shipped)
+  }
+
+  /// Tracking by line number
+  pub fn shipped_tracking(&self) -> Vec<(&str, &OrderLineTrackingInfo)> {
+    self
+      .orderLine
+      .iter()
+      .flat_map(|line| {
+        line
+          .shipped_tracking()
+          .into_iter()
+          .map(move |tracking| (line.lineNumber.as_str(), tracking))
+      })
+      .collect()
+  }
+}
+
+impl Order {
+  pub fn get_line(&self, line_number: &str) -> Option<&OrderLine> {
+    self.orderLines.get_line(line_number)
+  }
+
+  pub fn remaining_to_ship(&self) -> WalmartResult<u32> {
+    self.orderLines.remaining_to_ship()
+  }
+
+  pub fn is_fully_shipped(&self) -> WalmartResult<bool> {
+    self.orderLines.is_fully_shipped()
+  }
+
+  pub fn shipped_tracking(&self) -> Vec<(&str, &OrderLineTrackingInfo)> {
+    self.orderLines.shipped_tracking()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn line(ordered: &str, statuses: &[(&str, &str, Option<&str>)]) -> OrderLine {
+    let statuses: Vec<_> = statuses
+      .iter()
+      .map(|&(status, amount, tracking_number)| {
+        json!({
+          "status": status,
+          "statusQuantity": { "unitOfMeasurement": "EACH", "amount": amount },
+          "cancellationReason": null,
+          "trackingInfo": tracking_number.map(|tracking_number| json!({
+            "shipDateTime": 1502089301000i64,
+            "carrierName": { "otherCarrier": null, "carrier": "UPS" },
+            "methodCode": "Standard",
+            "trackingNumber": tracking_number,
+            "trackingURL": null
+          }))
+        })
+      })
+      .collect();
+    serde_json::from_value(json!({
+      "lineNumber": "1",
+      "item": { "productName": "Headphones", "sku": "h850" },
+      "charges": { "charge": [] },
+      "orderLineQuantity": { "unitOfMeasurement": "EACH", "amount": ordered },
+      "statusDate": null,
+      "orderLineStatuses": { "orderLineStatus": statuses },
+      "refund": null
+    }))
+    .unwrap()
+  }
+
+  #[test]
+  fn test_line_quantities() {
+    let partial = line(
+      "3",
+      &[("Shipped", "1", Some("1Z1")), ("Acknowledged", "2.0", None)],
+    );
+    let q = partial.get_quantities().unwrap();
+    assert_eq!((q.ordered, q.shipped, q.acknowledged), (3, 1, 2));
+    assert_eq!(partial.remaining_to_ship().unwrap(), 2);
+    assert!(!partial.is_fully_shipped().unwrap());
+    assert_eq!(
+      partial.shipped_tracking()[0]
+        .trackingNumber
+        .as_ref()
+        .unwrap(),
+      "1Z1"
+    );
+
+    let shipped = line(
+      "2",
+      &[("Shipped", "1", Some("1Z1")), ("Cancelled", "1", None)],
+    );
+    assert_eq!(shipped.remaining_to_ship().unwrap(), 0);
+    assert!(shipped.is_fully_shipped().unwrap());
+
+    let cancelled = line("1", &[("Cancelled", "1", None)]);
+    assert!(cancelled.is_cancelled().unwrap());
+    assert!(!cancelled.is_fully_shipped().unwrap());
+
+    let lines = OrderLines {
+      orderLine: vec![shipped, cancelled],
+    };
+    assert!(lines.is_fully_shipped().unwrap());
+    assert_eq!(lines.shipped_tracking().len(), 1);
+
+    assert!(line("1.5", &[]).remaining_to_ship().is_err());
+
+    let overflow = line(
+      "1",
+      &[("Shipped", "4294967295", None), ("Cancelled", "1", None)],
+    );
+    assert_eq!(overflow.get_quantities().unwrap().shipped, u32::MAX);
+    assert_eq!(overflow.remaining_to_ship().unwrap(), 0);
+  }
+}
@@ -1,13 +1,35 @@
 use crate::result::*;
 use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use serde_json::Value;
 use serde_urlencoded;
 
+mod carrier;
+mod enums;
+mod fulfillment;
+mod sync;
 mod types;
 
+use self::carrier::get_tracking_url;
+pub use self::carrier::validate_tracking_number;
+pub use self::enums::*;
+pub use self::fulfillment::LineQuantities;
+pub use self::sync::{
+  FileWatermarkStore, MemoryWatermarkStore, OrderSync, SyncReport, SyncWatermark, WatermarkStore,
+};
 pub use self::types::*;
-use crate::client::{Client, Method};
+#[cfg(feature = "async")]
+use crate::async_client::{AsyncClient, WalmartFuture};
+use crate::client::{Client, Method, WalmartMarketplace};
+use crate::paginate::Paginator;
 use crate::response::{parse_list_elements_json, parse_object_json, ListResponse};
+use crate::Money;
+#[cfg(feature = "async")]
+use futures::Future;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
 
 /// Query parameters for `get_all_released_orders`
 
@@ -26,26 +48,57 @@ pub struct QueryParams {
   pub sku: Option<String>,
   pub customerOrderId: Option<String>,
   pub purchaseOrderId: Option<String>,
-  pub status: Option<String>,
+  pub status: Option<OrderLineStatusKind>,
   pub createdStartDate: Option<DateTime<Utc>>,
   pub createdEndDate: Option<DateTime<Utc>>,
+  pub lastModifiedStartDate: Option<DateTime<Utc>>,
+  pub lastModifiedEndDate: Option<DateTime<Utc>>,
   pub fromExpectedShipDate: Option<DateTime<Utc>>,
   pub toExpectedShipDate: Option<DateTime<Utc>>,
   pub limit: Option<i32>,
   pub nextCursor: Option<String>,
-  pub shipNodeType: Option<String>,
+  /// `SellerFulfilled` if not set (Only USA)
+  pub shipNodeType: Option<ShipNodeType>,
 }
 
+/// Query parameters for `get_all_wfs_orders`, `offset` is only used by Canada
 #[derive(Debug, Serialize, Default)]
+#[allow(non_snake_case)]
 pub struct WFSQueryParams {
   pub customerOrderId: Option<String>,
   pub createdStartDate: Option<DateTime<Utc>>,
   pub createdEndDate: Option<DateTime<Utc>>,
-  pub status: Option<String>,
+  pub status: Option<OrderLineStatusKind>,
   pub limit: Option<i32>,
   pub offset: Option<i32>,
 }
 
+impl WFSQueryParams {
+  /// US WFS orders are `get_all_orders` filtered by ship node type
+  fn to_query_string(&self, marketplace: WalmartMarketplace) -> WalmartResult<String> {
+    match marketplace {
+      WalmartMarketplace::Canada => serde_urlencoded::to_string(self).map_err(Into::into),
+      WalmartMarketplace::USA => serde_urlencoded::to_string(QueryParams {
+        customerOrderId: self.customerOrderId.clone(),
+        createdStartDate: self.createdStartDate,
+        createdEndDate: self.createdEndDate,
+        status: self.status.clone(),
+        limit: self.limit,
+        shipNodeType: Some(ShipNodeType::WFSFulfilled),
+        ..Default::default()
+      })
+      .map_err(Into::into),
+    }
+  }
+}
+
+fn get_wfs_orders_path(marketplace: WalmartMarketplace) -> &'static str {
+  match marketplace {
+    WalmartMarketplace::Canada => "/v3/orders/wfs",
+    WalmartMarketplace::USA => "/v3/orders",
+  }
+}
+
 #[derive(Debug, Clone)]
 #[allow(non_snake_case)]
 pub struct ShipParams {
@@ -54,6 +107,7 @@ pub struct ShipParams {
   pub carrierName: Option<String>,
   pub methodCode: String,
   pub trackingNumber: String,
+  /// Derived from `carrierName` if empty
   pub trackingURL: String,
   pub otherCarrier: Option<String>,
   pub unitOfMeasurement: Option<String>,
@@ -62,9 +116,14 @@ pub struct ShipParams {
 }
 
 impl ShipParams {
+  /// Checks the tracking number format of known carriers, `ship_order` doesn't call it
+  pub fn validate(&self) -> WalmartResult<()> {
+    validate_tracking_number(self.carrierName.as_deref(), &self.trackingNumber)
+      .map_err(|err| format!("line {}: {}", self.lineNumber, err).into())
+  }
+
   pub fn to_value(&self) -> Value {
-    let timestamp: i64 =
-      self.shipDateTime.timestamp() * 1000 + self.shipDateTime.timestamp_subsec_millis() as i64;
+    let timestamp = self.shipDateTime.timestamp_millis();
     json!({
       "lineNumber": self.lineNumber,
       "shipFromCountry": self.shipFromCountry,
@@ -84,7 +143,11 @@ impl ShipParams {
               },
               "methodCode": self.methodCode,
               "trackingNumber": self.trackingNumber,
-              "trackingURL": self.trackingURL
+              "trackingURL": get_tracking_url(
+                self.carrierName.as_deref(),
+                &self.trackingNumber,
+                &self.trackingURL,
+              ),
             }
           }
         ]
@@ -93,62 +156,403 @@ impl ShipParams {
   }
 }
 
+fn get_ship_order_body(lines: &[ShipParams]) -> Value {
+  let line_values: Vec<_> = lines.iter().map(ShipParams::to_value).collect();
+  json!({
+    "orderShipment": {
+      "orderLines": {
+        "orderLine": line_values,
+      }
+    }
+  })
+}
+
+/// A package of a `LineShipment`
+#[derive(Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct ShipPackage {
+  /// Units in this package
+  pub amount: u32,
+  pub unitOfMeasurement: Option<String>,
+  pub shipDateTime: DateTime<Utc>,
+  pub carrierName: Option<String>,
+  pub otherCarrier: Option<String>,
+  pub methodCode: String,
+  pub trackingNumber: String,
+  /// Derived from `carrierName` if empty
+  pub trackingURL: String,
+}
+
+impl ShipPackage {
+  pub fn to_value(&self) -> Value {
+    json!({
+      "status": OrderLineStatusKind::Shipped,
+      "statusQuantity": {
+        "unitOfMeasurement": self.unitOfMeasurement.clone().unwrap_or_else(|| "EACH".to_owned()),
+        "amount": self.amount.to_string(),
+      },
+      "trackingInfo": {
+        "shipDateTime": self.shipDateTime.timestamp_millis(),
+        "carrierName": {
+          "otherCarrier": self.otherCarrier,
+          "carrier": self.carrierName,
+        },
+        "methodCode": self.methodCode,
+        "trackingNumber": self.trackingNumber,
+        "trackingURL": get_tracking_url(
+          self.carrierName.as_deref(),
+          &self.trackingNumber,
+          &self.trackingURL,
+        ),
+      }
+    })
+  }
+}
+
+/// Shipment of an order line in one or more packages
+#[derive(Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct LineShipment {
+  pub lineNumber: String,
+  pub shipFromCountry: String,
+  pub packages: Vec<ShipPackage>,
+}
+
+impl LineShipment {
+  /// Checks the tracking number formats of known carriers, `ship_order_packages` doesn't call it
+  pub fn validate(&self) -> WalmartResult<()> {
+    for package in &self.packages {
+      validate_tracking_number(package.carrierName.as_deref(), &package.trackingNumber)
+        .map_err(|err| -> WalmartError { format!("line {}: {}", self.lineNumber, err).into() })?;
+    }
+    Ok(())
+  }
+
+  /// Units in all packages
+  pub fn get_amount(&self) -> u32 {
+    self.packages.iter().map(|package| package.amount).sum()
+  }
+
+  pub fn to_value(&self) -> Value {
+    let package_values: Vec<_> = self.packages.iter().map(ShipPackage::to_value).collect();
+    json!({
+      "lineNumber": self.lineNumber,
+      "shipFromCountry": self.shipFromCountry,
+      "orderLineStatuses": {
+        "orderLineStatus": package_values
+      }
+    })
+  }
+}
+
+/// Checks shipments against the remaining quantities of `order`'s lines
+pub fn validate_shipments(order: &Order, shipments: &[LineShipment]) -> WalmartResult<()> {
+  let mut amounts: Vec<(&str, u32)> = vec![];
+  for shipment in shipments {
+    if shipment.packages.iter().any(|package| package.amount == 0) || shipment.packages.is_empty() {
+      return Err(
+        format!(
+          "line {} of order {}: every package must contain at least one unit",
+          shipment.lineNumber, order.purchaseOrderId
+        )
+        .into(),
+      );
+    }
+    match amounts
+      .iter_mut()
+      .find(|(line_number, _)| *line_number == shipment.lineNumber)
+    {
+      Some(&mut (_, ref mut amount)) => *amount += shipment.get_amount(),
+      None => amounts.push((&shipment.lineNumber, shipment.get_amount())),
+    }
+  }
+
+  for (line_number, amount) in amounts {
+    let remaining = order
+      .get_line(line_number)
+      .ok_or_else(|| -> WalmartError {
+        format!(
+          "line {} was not found in order {}",
+          line_number, order.purchaseOrderId
+        )
+        .into()
+      })?
+      .remaining_to_ship()?;
+    if amount > remaining {
+      return Err(
+        format!(
+          "line {} of order {}: shipping {} units but only {} remain",
+          line_number, order.purchaseOrderId, amount, remaining
+        )
+        .into(),
+      );
+    }
+  }
+  Ok(())
+}
+
+fn get_line_shipment_body(shipments: &[LineShipment]) -> Value {
+  let line_values: Vec<_> = shipments.iter().map(LineShipment::to_value).collect();
+  json!({
+    "orderShipment": {
+      "orderLines": {
+        "orderLine": line_values,
+      }
+    }
+  })
+}
+
+#[derive(Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct CancelParams {
+  pub lineNumber: String,
+  pub cancellationReason: CancellationReason,
+  pub unitOfMeasurement: Option<String>,
+  pub amount: Option<String>,
+}
+
+impl CancelParams {
+  pub fn to_value(&self) -> Value {
+    json!({
+      "lineNumber": self.lineNumber,
+      "orderLineStatuses": {
+        "orderLineStatus": [
+          {
+            "status": OrderLineStatusKind::Cancelled,
+            "cancellationReason": self.cancellationReason,
+            "statusQuantity": {
+              "unitOfMeasurement": self.unitOfMeasurement.clone().unwrap_or_else(|| "EACH".to_owned()),
+              "amount": self.amount.clone().unwrap_or_else(|| "1".to_owned()),
+            }
+          }
+        ]
+      }
+    })
+  }
+}
+
+fn get_cancel_order_body(lines: &[CancelParams]) -> Value {
+  let line_values: Vec<_> = lines.iter().map(CancelParams::to_value).collect();
+  json!({
+    "orderCancellation": {
+      "orderLines": {
+        "orderLine": line_values,
+      }
+    }
+  })
+}
+
+/// A refunded charge, amounts are positive and sent negated as Walmart expects
+#[derive(Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct RefundChargeParams {
+  pub refundReason: RefundReason,
+  pub chargeType: ChargeType,
+  pub chargeName: String,
+  pub amount: Money,
+  pub tax: Option<Money>,
+}
+
+impl RefundChargeParams {
+  pub fn product(refund_reason: RefundReason, amount: Money, tax: Option<Money>) -> Self {
+    RefundChargeParams {
+      refundReason: refund_reason,
+      chargeType: ChargeType::Product,
+      chargeName: "Item Price".to_string(),
+      amount,
+      tax,
+    }
+  }
+
+  pub fn shipping(refund_reason: RefundReason, amount: Money, tax: Option<Money>) -> Self {
+    RefundChargeParams {
+      refundReason: refund_reason,
+      chargeType: ChargeType::Shipping,
+      chargeName: "Shipping Price".to_string(),
+      amount,
+      tax,
+    }
+  }
+
+  pub fn to_value(&self) -> WalmartResult<Value> {
+    let tax = match self.tax {
+      Some(ref tax) => Some(json!({
+        "taxName": format!("{} Tax", self.chargeName),
+        "taxAmount": get_refund_amount_value(tax)?,
+      })),
+      None => None,
+    };
+    Ok(json!({
+      "refundReason": self.refundReason,
+      "charge": {
+        "chargeType": self.chargeType,
+        "chargeName": self.chargeName,
+        "chargeAmount": get_refund_amount_value(&self.amount)?,
+        "tax": tax,
+      }
+    }))
+  }
+}
+
+/// Walmart expects a negative JSON number
+fn get_refund_amount_value(money: &Money) -> WalmartResult<Value> {
+  let amount = (-money.amount.abs()).to_string();
+  let amount = serde_json::Number::from_str(&amount).map_err(|err| -> WalmartError {
+    format!("invalid refund amount {}: {}", amount, err).into()
+  })?;
+  Ok(json!({
+    "currency": money.currency,
+    "amount": amount,
+  }))
+}
+
+#[derive(Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct RefundParams {
+  pub lineNumber: String,
+  pub isFullRefund: bool,
+  pub refundComments: Option<String>,
+  pub charges: Vec<RefundChargeParams>,
+}
+
+impl RefundParams {
+  pub fn to_value(&self) -> WalmartResult<Value> {
+    let charge_values = self
+      .charges
+      .iter()
+      .map(RefundChargeParams::to_value)
+      .collect::<WalmartResult<Vec<_>>>()?;
+    Ok(json!({
+      "lineNumber": self.lineNumber,
+      "isFullRefund": self.isFullRefund,
+      "refunds": {
+        "refund": [
+          {
+            "refundComments": self.refundComments,
+            "refundCharges": {
+              "refundCharge": charge_values,
+            }
+          }
+        ]
+      }
+    }))
+  }
+}
+
+fn get_refund_order_body(purchase_order_id: &str, lines: &[RefundParams]) -> WalmartResult<Value> {
+  let line_values = lines
+    .iter()
+    .map(RefundParams::to_value)
+    .collect::<WalmartResult<Vec<_>>>()?;
+  Ok(json!({
+    "orderRefund": {
+      "purchaseOrderId": purchase_order_id,
+      "orderLines": {
+        "orderLine": line_values,
+      }
+    }
+  }))
+}
+
 pub type OrderList = ListResponse<Order>;
 pub type OrderWFSList = ListResponse<OrderWFS>;
 
 impl Client {
   pub fn get_all_released_orders(&self, params: &ReleasedQueryParams) -> WalmartResult<OrderList> {
     let qs = serde_urlencoded::to_string(params)?;
-    let mut res = self.send(self.request_json(Method::GET, "/v3/orders/released", qs)?)?;
+    let mut res =
+      self.send_with(|| self.request_json(Method::GET, "/v3/orders/released", qs.clone()))?;
     parse_list_elements_json(res.status(), &mut res, "order").map_err(Into::into)
   }
 
   pub fn get_all_orders(&self, params: &QueryParams) -> WalmartResult<OrderList> {
-    let mut res = self.send(self.request_json(
-      Method::GET,
-      "/v3/orders",
-      serde_urlencoded::to_string(params)?,
-    )?)?;
+    let qs = serde_urlencoded::to_string(params)?;
+    let mut res = self.send_with(|| self.request_json(Method::GET, "/v3/orders", qs.clone()))?;
     parse_list_elements_json(res.status(), &mut res, "order").map_err(Into::into)
   }
 
-  /// Get all WFS orders (Only Canada)
+  /// Get all WFS orders
   pub fn get_all_wfs_orders(&self, params: &WFSQueryParams) -> WalmartResult<OrderWFSList> {
-    let mut res = self.send(self.request_json(
-      Method::GET,
-      "/v3/orders/wfs",
-      serde_urlencoded::to_string(params)?,
-    )?)?;
+    let marketplace = self.get_marketplace();
+    let qs = params.to_query_string(marketplace)?;
+    let path = get_wfs_orders_path(marketplace);
+    let mut res = self.send_with(|| self.request_json(Method::GET, path, qs.clone()))?;
     parse_list_elements_json(res.status(), &mut res, "order").map_err(Into::into)
   }
 
   pub fn get_all_orders_by_next_cursor(&self, next_cursor: &str) -> WalmartResult<OrderList> {
-    use url::form_urlencoded;
-    let mut res = self.send(
-      self.request_json(
-        Method::GET,
-        "/v3/orders",
-        form_urlencoded::parse((&next_cursor).as_bytes())
-          .into_owned()
-          .collect::<Vec<_>>(),
-      )?,
-    )?;
+    self.get_list_by_next_cursor("/v3/orders", next_cursor)
+  }
+
+  pub fn get_all_released_orders_by_next_cursor(
+    &self,
+    next_cursor: &str,
+  ) -> WalmartResult<OrderList> {
+    self.get_list_by_next_cursor("/v3/orders/released", next_cursor)
+  }
+
+  /// Get all WFS orders by `nextCursor`
+  pub fn get_all_wfs_orders_by_next_cursor(
+    &self,
+    next_cursor: &str,
+  ) -> WalmartResult<OrderWFSList> {
+    self.get_list_by_next_cursor(get_wfs_orders_path(self.get_marketplace()), next_cursor)
+  }
+
+  /// `nextCursor` is the query string of the next page, e.g. `?limit=10&hasMoreElements=true&soIndex=10...`
+  fn get_list_by_next_cursor<T>(
+    &self,
+    path: &str,
+    next_cursor: &str,
+  ) -> WalmartResult<ListResponse<T>>
+  where
+    T: Serialize + DeserializeOwned,
+  {
+    let qs = next_cursor.trim_start_matches('?');
+    let mut res = self.send_with(|| self.request_json(Method::GET, path, qs))?;
     parse_list_elements_json(res.status(), &mut res, "order").map_err(Into::into)
   }
 
+  /// Iterates over `get_all_orders` pages
+  pub fn orders<'a>(&'a self, params: &'a QueryParams) -> Paginator<'a, OrderList> {
+    Paginator::cursor(
+      move || self.get_all_orders(params),
+      move |cursor| self.get_all_orders_by_next_cursor(cursor),
+    )
+  }
+
+  /// Iterates over `get_all_released_orders` pages
+  pub fn released_orders<'a>(
+    &'a self,
+    params: &'a ReleasedQueryParams,
+  ) -> Paginator<'a, OrderList> {
+    Paginator::cursor(
+      move || self.get_all_released_orders(params),
+      move |cursor| self.get_all_released_orders_by_next_cursor(cursor),
+    )
+  }
+
+  /// Iterates over `get_all_wfs_orders` pages
+  pub fn wfs_orders<'a>(&'a self, params: &'a WFSQueryParams) -> Paginator<'a, OrderWFSList> {
+    Paginator::cursor(
+      move || self.get_all_wfs_orders(params),
+      move |cursor| self.get_all_wfs_orders_by_next_cursor(cursor),
+    )
+  }
+
   pub fn get_order(&self, purchase_order_id: &str) -> WalmartResult<Order> {
     let path = format!("/v3/orders/{}", purchase_order_id);
-    let mut res = self.send(self.request_json(Method::GET, &path, ())?)?;
+    let mut res = self.send_with(|| self.request_json(Method::GET, &path, ()))?;
     parse_object_json(res.status(), &mut res, "order").map_err(Into::into)
   }
 
   pub fn ack_order(&self, purchase_order_id: &str) -> WalmartResult<Order> {
     let path = format!("/v3/orders/{}/acknowledge", purchase_order_id);
-    let mut res = self.send(
+    let mut res = self.send_with(|| {
       self
-        .request_json(Method::POST, &path, ())?
-        .json(&Vec::<i32>::new()),
-    )?;
+        .request_json(Method::POST, &path, ())
+        .map(|req| req.json(&Vec::<i32>::new()))
+    })?;
     parse_object_json(res.status(), &mut res, "order").map_err(Into::into)
   }
 
@@ -161,24 +565,324 @@ impl Client {
   }
 
   pub fn ship_order(&self, purchase_order_id: &str, lines: &[ShipParams]) -> WalmartResult<Order> {
-    let line_values: Vec<_> = lines.into_iter().map(ShipParams::to_value).collect();
-    let body = json!({
-      "orderShipment": {
-        "orderLines": {
-          "orderLine": line_values,
-        }
-      }
-    });
+    let body = get_ship_order_body(lines);
     let path = format!("/v3/orders/{}/shipping", purchase_order_id);
-    let mut res = self.send(
+    let mut res = self.send_with(|| {
       self
         .request_json(
           Method::POST,
           &path,
           vec![("purchaseOrderId", purchase_order_id)],
-        )?
-        .json(&body),
-    )?;
+        )
+        .map(|req| req.json(&body))
+    })?;
     parse_object_json(res.status(), &mut res, "order").map_err(Into::into)
   }
+
+  /// Ships lines in one or more packages, after checking the quantities against the current order
+  pub fn ship_order_packages(
+    &self,
+    purchase_order_id: &str,
+    shipments: &[LineShipment],
+  ) -> WalmartResult<Order> {
+    let order = self.get_order(purchase_order_id)?;
+    validate_shipments(&order, shipments)?;
+
+    let body = get_line_shipment_body(shipments);
+    let path = format!("/v3/orders/{}/shipping", purchase_order_id);
+    let mut res = self.send_with(|| {
+      self
+        .request_json(
+          Method::POST,
+          &path,
+          vec![("purchaseOrderId", purchase_order_id)],
+        )
+        .map(|req| req.json(&body))
+    })?;
+    parse_object_json(res.status(), &mut res, "order").map_err(Into::into)
+  }
+
+  pub fn cancel_order_lines(
+    &self,
+    purchase_order_id: &str,
+    lines: &[CancelParams],
+  ) -> WalmartResult<Order> {
+    let body = get_cancel_order_body(lines);
+    let path = format!("/v3/orders/{}/cancel", purchase_order_id);
+    let mut res = self.send_with(|| {
+      self
+        .request_json(Method::POST, &path, ())
+        .map(|req| req.json(&body))
+    })?;
+    parse_object_json(res.status(), &mut res, "order").map_err(Into::into)
+  }
+
+  pub fn refund_order_lines(
+    &self,
+    purchase_order_id: &str,
+    lines: &[RefundParams],
+  ) -> WalmartResult<Order> {
+    let body = get_refund_order_body(purchase_order_id, lines)?;
+    let path = format!("/v3/orders/{}/refund", purchase_order_id);
+    let mut res = self.send_with(|| {
+      self
+        .request_json(Method::POST, &path, ())
+        .map(|req| req.json(&body))
+    })?;
+    parse_object_json(res.status(), &mut res, "order").map_err(Into::into)
+  }
+}
+
+/// Outcome of acknowledging one order in `ack_all_released`
+#[derive(Debug)]
+pub struct AckResult {
+  pub purchase_order_id: String,
+  pub result: WalmartResult<Order>,
+}
+
+impl Client {
+  /// Acknowledges every released order, at most `max_parallel` at a time.
+  ///
+  /// Returns a result per order in release order, or an error if listing released orders fails.
+  pub fn ack_all_released(&self, max_parallel: usize) -> WalmartResult<Vec<AckResult>> {
+    // acknowledged orders leave the released list, so collect ids before paging shifts
+    let params = ReleasedQueryParams {
+      limit: Some(200),
+      ..Default::default()
+    };
+    let ids: Vec<String> = self
+      .released_orders(&params)
+      .items()
+      .map(|order| order.map(|order| order.purchaseOrderId))
+      .collect::<WalmartResult<_>>()?;
+
+    let next = AtomicUsize::new(0);
+    let workers = max_parallel.max(1).min(ids.len());
+    let mut results: Vec<(usize, AckResult)> = thread::scope(|scope| {
+      let handles: Vec<_> = (0..workers)
+        .map(|_| {
+          scope.spawn(|| {
+            let mut results = vec![];
+            loop {
+              let i = next.fetch_add(1, Ordering::SeqCst);
+              let purchase_order_id = match ids.get(i) {
+                Some(id) => id.clone(),
+                None => return results,
+              };
+              let result = self.ack_order(&purchase_order_id);
+              if let Err(ref err) = result {
+                warn!("ack order {}: {}", purchase_order_id, err);
+              }
+              results.push((
+                i,
+                AckResult {
+                  purchase_order_id,
+                  result,
+                },
+              ));
+            }
+          })
+        })
+        .collect();
+      handles
+        .into_iter()
+        .flat_map(|handle| handle.join().unwrap())
+        .collect()
+    });
+    results.sort_by_key(|&(i, _)| i);
+    Ok(results.into_iter().map(|(_, result)| result).collect())
+  }
+}
+
+#[cfg(feature = "async")]
+impl AsyncClient {
+  pub fn get_all_released_orders(&self, params: &ReleasedQueryParams) -> WalmartFuture<OrderList> {
+    let qs = try_future!(serde_urlencoded::to_string(params));
+    self.send_parse(
+      self.request_json(Method::GET, "/v3/orders/released", qs),
+      |status, body| {
+        parse_list_elements_json(status, &mut body.as_slice(), "order").map_err(Into::into)
+      },
+    )
+  }
+
+  pub fn get_all_orders(&self, params: &QueryParams) -> WalmartFuture<OrderList> {
+    let qs = try_future!(serde_urlencoded::to_string(params));
+    self.send_parse(
+      self.request_json(Method::GET, "/v3/orders", qs),
+      |status, body| {
+        parse_list_elements_json(status, &mut body.as_slice(), "order").map_err(Into::into)
+      },
+    )
+  }
+
+  /// Get all WFS orders
+  pub fn get_all_wfs_orders(&self, params: &WFSQueryParams) -> WalmartFuture<OrderWFSList> {
+    let marketplace = self.get_marketplace();
+    let qs = try_future!(params.to_query_string(marketplace));
+    self.send_parse(
+      self.request_json(Method::GET, get_wfs_orders_path(marketplace), qs),
+      |status, body| {
+        parse_list_elements_json(status, &mut body.as_slice(), "order").map_err(Into::into)
+      },
+    )
+  }
+
+  pub fn get_all_orders_by_next_cursor(&self, next_cursor: &str) -> WalmartFuture<OrderList> {
+    self.send_parse(
+      self.request_json(
+        Method::GET,
+        "/v3/orders",
+        next_cursor.trim_start_matches('?'),
+      ),
+      |status, body| {
+        parse_list_elements_json(status, &mut body.as_slice(), "order").map_err(Into::into)
+      },
+    )
+  }
+
+  pub fn get_all_released_orders_by_next_cursor(
+    &self,
+    next_cursor: &str,
+  ) -> WalmartFuture<OrderList> {
+    self.send_parse(
+      self.request_json(
+        Method::GET,
+        "/v3/orders/released",
+        next_cursor.trim_start_matches('?'),
+      ),
+      |status, body| {
+        parse_list_elements_json(status, &mut body.as_slice(), "order").map_err(Into::into)
+      },
+    )
+  }
+
+  /// Get all WFS orders by `nextCursor`
+  pub fn get_all_wfs_orders_by_next_cursor(
+    &self,
+    next_cursor: &str,
+  ) -> WalmartFuture<OrderWFSList> {
+    self.send_parse(
+      self.request_json(
+        Method::GET,
+        get_wfs_orders_path(self.get_marketplace()),
+        next_cursor.trim_start_matches('?'),
+      ),
+      |status, body| {
+        parse_list_elements_json(status, &mut body.as_slice(), "order").map_err(Into::into)
+      },
+    )
+  }
+
+  pub fn get_order(&self, purchase_order_id: &str) -> WalmartFuture<Order> {
+    let path = format!("/v3/orders/{}", purchase_order_id);
+    self.send_parse(self.request_json(Method::GET, &path, ()), |status, body| {
+      parse_object_json(status, &mut body.as_slice(), "order").map_err(Into::into)
+    })
+  }
+
+  pub fn ack_order(&self, purchase_order_id: &str) -> WalmartFuture<Order> {
+    let path = format!("/v3/orders/{}/acknowledge", purchase_order_id);
+    self.send_parse(
+      Box::new(
+        self
+          .request_json(Method::POST, &path, ())
+          .map(|req| req.json(&Vec::<i32>::new())),
+      ),
+      |status, body| parse_object_json(status, &mut body.as_slice(), "order").map_err(Into::into),
+    )
+  }
+
+  pub fn ship_order_line(
+    &self,
+    purchase_order_id: &str,
+    line: &ShipParams,
+  ) -> WalmartFuture<Order> {
+    self.ship_order(purchase_order_id, std::slice::from_ref(line))
+  }
+
+  pub fn ship_order(&self, purchase_order_id: &str, lines: &[ShipParams]) -> WalmartFuture<Order> {
+    let body = get_ship_order_body(lines);
+    let path = format!("/v3/orders/{}/shipping", purchase_order_id);
+    self.send_parse(
+      Box::new(
+        self
+          .request_json(
+            Method::POST,
+            &path,
+            vec![("purchaseOrderId", purchase_order_id)],
+          )
+          .map(move |req| req.json(&body)),
+      ),
+      |status, body| parse_object_json(status, &mut body.as_slice(), "order").map_err(Into::into),
+    )
+  }
+
+  pub fn ship_order_packages(
+    &self,
+    purchase_order_id: &str,
+    shipments: &[LineShipment],
+  ) -> WalmartFuture<Order> {
+    let client = self.clone();
+    let purchase_order_id = purchase_order_id.to_string();
+    let shipments = shipments.to_vec();
+    Box::new(
+      self
+        .get_order(&purchase_order_id)
+        .and_then(move |order| -> WalmartFuture<Order> {
+          try_future!(validate_shipments(&order, &shipments));
+
+          let body = get_line_shipment_body(&shipments);
+          let path = format!("/v3/orders/{}/shipping", purchase_order_id);
+          client.send_parse(
+            Box::new(
+              client
+                .request_json(
+                  Method::POST,
+                  &path,
+                  vec![("purchaseOrderId", purchase_order_id.as_str())],
+                )
+                .map(move |req| req.json(&body)),
+            ),
+            |status, body| {
+              parse_object_json(status, &mut body.as_slice(), "order").map_err(Into::into)
+            },
+          )
+        }),
+    )
+  }
+
+  pub fn cancel_order_lines(
+    &self,
+    purchase_order_id: &str,
+    lines: &[CancelParams],
+  ) -> WalmartFuture<Order> {
+    let body = get_cancel_order_body(lines);
+    let path = format!("/v3/orders/{}/cancel", purchase_order_id);
+    self.send_parse(
+      Box::new(
+        self
+          .request_json(Method::POST, &path, ())
+          .map(move |req| req.json(&body)),
+      ),
+      |status, body| parse_object_json(status, &mut body.as_slice(), "order").map_err(Into::into),
+    )
+  }
+
+  pub fn refund_order_lines(
+    &self,
+    purchase_order_id: &str,
+    lines: &[RefundParams],
+  ) -> WalmartFuture<Order> {
+    let body = try_future!(get_refund_order_body(purchase_order_id, lines));
+    let path = format!("/v3/orders/{}/refund", purchase_order_id);
+    self.send_parse(
+      Box::new(
+        self
+          .request_json(Method::POST, &path, ())
+          .map(move |req| req.json(&body)),
+      ),
+      |status, body| parse_object_json(status, &mut body.as_slice(), "order").map_err(Into::into),
+    )
+  }
 }
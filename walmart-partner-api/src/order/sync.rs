@@ -0,0 +1,246 @@
+//! Incremental sync of orders created or modified since the last run
+//!
+//! Each run queries from the previous watermark minus an overlap window, so orders
+//! Walmart indexes late are still found. Orders already yielded within the overlap
+//! window are skipped unless a line status changed since.
+
+use super::types::*;
+use super::QueryParams;
+use crate::client::Client;
+use crate::result::*;
+use crate::utils::{read_json_file, write_json_file};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+/// Progress of an `OrderSync`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncWatermark {
+  /// End of the last completed run
+  pub synced_to: Option<DateTime<Utc>>,
+  /// Last modification time of orders yielded within the overlap window, by `purchaseOrderId`
+  pub recent: BTreeMap<String, DateTime<Utc>>,
+}
+
+impl SyncWatermark {
+  fn is_new(&self, purchase_order_id: &str, modified: DateTime<Utc>) -> bool {
+    self
+      .recent
+      .get(purchase_order_id)
+      .map_or(true, |yielded| modified > *yielded)
+  }
+}
+
+/// Where `OrderSync` reads and writes watermarks, keyed by sync name
+pub trait WatermarkStore: Send + Sync {
+  fn get(&self, key: &str) -> WalmartResult<Option<SyncWatermark>>;
+  fn set(&self, key: &str, watermark: &SyncWatermark) -> WalmartResult<()>;
+}
+
+/// Keeps watermarks in process memory
+#[derive(Debug, Default)]
+pub struct MemoryWatermarkStore {
+  watermarks: RwLock<HashMap<String, SyncWatermark>>,
+}
+
+impl MemoryWatermarkStore {
+  pub fn new() -> Self {
+    Default::default()
+  }
+}
+
+impl WatermarkStore for MemoryWatermarkStore {
+  fn get(&self, key: &str) -> WalmartResult<Option<SyncWatermark>> {
+    Ok(self.watermarks.read().unwrap().get(key).cloned())
+  }
+
+  fn set(&self, key: &str, watermark: &SyncWatermark) -> WalmartResult<()> {
+    self
+      .watermarks
+      .write()
+      .unwrap()
+      .insert(key.to_string(), watermark.clone());
+    Ok(())
+  }
+}
+
+/// Keeps watermarks in a JSON file, the default store
+#[derive(Debug)]
+pub struct FileWatermarkStore {
+  path: PathBuf,
+}
+
+impl FileWatermarkStore {
+  pub fn new<P: AsRef<Path>>(path: P) -> Self {
+    FileWatermarkStore {
+      path: path.as_ref().to_path_buf(),
+    }
+  }
+}
+
+impl WatermarkStore for FileWatermarkStore {
+  fn get(&self, key: &str) -> WalmartResult<Option<SyncWatermark>> {
+    let mut watermarks: HashMap<String, SyncWatermark> = read_json_file(&self.path)?;
+    Ok(watermarks.remove(key))
+  }
+
+  fn set(&self, key: &str, watermark: &SyncWatermark) -> WalmartResult<()> {
+    let mut watermarks: HashMap<String, SyncWatermark> = read_json_file(&self.path)?;
+    watermarks.insert(key.to_string(), watermark.clone());
+    write_json_file(&self.path, &watermarks)
+  }
+}
+
+/// Result of `OrderSync::run`
+#[derive(Debug, Clone)]
+pub struct SyncReport {
+  /// Orders passed to the callback
+  pub yielded: usize,
+  /// Orders returned by Walmart but already yielded
+  pub skipped: usize,
+  pub synced_to: DateTime<Utc>,
+}
+
+/// Fetches orders created or modified since the last run, see the module docs
+pub struct OrderSync<'a> {
+  client: &'a Client,
+  store: Arc<dyn WatermarkStore>,
+  key: String,
+  overlap: Duration,
+  initial_lookback: Duration,
+  modified_orders: bool,
+  limit: Option<i32>,
+}
+
+impl<'a> OrderSync<'a> {
+  /// Keeps the watermark in the JSON file at `path`
+  pub fn new<P: AsRef<Path>>(client: &'a Client, path: P) -> Self {
+    Self::with_store(client, Arc::new(FileWatermarkStore::new(path)))
+  }
+
+  pub fn with_store(client: &'a Client, store: Arc<dyn WatermarkStore>) -> Self {
+    OrderSync {
+      client,
+      store,
+      key: "orders".to_string(),
+      overlap: Duration::minutes(30),
+      initial_lookback: Duration::days(7),
+      modified_orders: true,
+      limit: Some(100),
+    }
+  }
+
+  /// Sets the watermark key, to keep several syncs in one store, default `orders`
+  pub fn key(mut self, key: &str) -> Self {
+    self.key = key.to_string();
+    self
+  }
+
+  /// Sets how far before the watermark each run starts, default 30 minutes
+  pub fn overlap(mut self, overlap: Duration) -> Self {
+    self.overlap = overlap;
+    self
+  }
+
+  /// Sets how far back the first run starts, default 7 days
+  pub fn initial_lookback(mut self, initial_lookback: Duration) -> Self {
+    self.initial_lookback = initial_lookback;
+    self
+  }
+
+  /// Sets whether orders modified since the watermark are fetched, default `true`.
+  ///
+  /// Uses `lastModifiedStartDate`, disable it for marketplaces which don't support it.
+  pub fn modified_orders(mut self, modified_orders: bool) -> Self {
+    self.modified_orders = modified_orders;
+    self
+  }
+
+  /// Sets the page size
+  pub fn limit(mut self, limit: i32) -> Self {
+    self.limit = Some(limit);
+    self
+  }
+
+  pub fn get_watermark(&self) -> WalmartResult<SyncWatermark> {
+    Ok(self.store.get(&self.key)?.unwrap_or_default())
+  }
+
+  /// Passes new and changed orders to `f`, oldest query first.
+  ///
+  /// The watermark only advances when every order was handled, if `f` fails
+  /// the orders it already handled are recorded and the next run resumes after them.
+  pub fn run<F>(&self, mut f: F) -> WalmartResult<SyncReport>
+  where
+    F: FnMut(Order) -> WalmartResult<()>,
+  {
+    let now = Utc::now();
+    let mut watermark = self.get_watermark()?;
+    let start = match watermark.synced_to {
+      Some(synced_to) => synced_to - self.overlap,
+      None => now - self.initial_lookback,
+    };
+
+    let mut queries = vec![QueryParams {
+      createdStartDate: Some(start),
+      createdEndDate: Some(now),
+      limit: self.limit,
+      ..Default::default()
+    }];
+    if self.modified_orders {
+      queries.push(QueryParams {
+        lastModifiedStartDate: Some(start),
+        lastModifiedEndDate: Some(now),
+        limit: self.limit,
+        ..Default::default()
+      });
+    }
+
+    let mut report = SyncReport {
+      yielded: 0,
+      skipped: 0,
+      synced_to: now,
+    };
+    let mut seen = HashSet::new();
+    for params in &queries {
+      for order in self.client.orders(params).items() {
+        let order = order?;
+        if !seen.insert(order.purchaseOrderId.clone()) {
+          continue;
+        }
+        let modified = get_last_modified(&order);
+        if !watermark.is_new(&order.purchaseOrderId, modified) {
+          report.skipped += 1;
+          continue;
+        }
+        let purchase_order_id = order.purchaseOrderId.clone();
+        if let Err(err) = f(order) {
+          self.store.set(&self.key, &watermark)?;
+          return Err(err);
+        }
+        watermark.recent.insert(purchase_order_id, modified);
+        report.yielded += 1;
+      }
+    }
+
+    // older orders are not returned by the next run
+    let next_start = now - self.overlap;
+    watermark
+      .recent
+      .retain(|_, modified| *modified >= next_start);
+    watermark.synced_to = Some(now);
+    self.store.set(&self.key, &watermark)?;
+    Ok(report)
+  }
+}
+
+/// The latest of `orderDate` and line `statusDate`s
+fn get_last_modified(order: &Order) -> DateTime<Utc> {
+  order
+    .orderLines
+    .orderLine
+    .iter()
+    .filter_map(|line| line.statusDate)
+    .fold(order.orderDate, |latest, date| latest.max(date))
+}
@@ -1,9 +1,8 @@
-#[derive(Debug, Serialize, Deserialize)]
-#[allow(non_snake_case)]
-pub struct CurrencyAmount {
-  pub currency: String,
-  pub amount: f64,
-}
+use super::enums::*;
+use crate::utils::*;
+use crate::Money;
+use chrono::{DateTime, Utc};
+use serde_json::{Map, Value};
 
 #[derive(Debug, Serialize, Deserialize)]
 #[allow(non_snake_case)]
@@ -22,8 +21,17 @@ pub struct PostalAddress {
 #[allow(non_snake_case)]
 pub struct ShippingInformation {
   pub phone: Option<String>,
-  pub estimatedDeliveryDate: Option<i64>,
-  pub estimatedShipDate: i64,
+  #[serde(
+    default,
+    deserialize_with = "deserialize_timestamp_opt",
+    serialize_with = "serialize_timestamp_opt"
+  )]
+  pub estimatedDeliveryDate: Option<DateTime<Utc>>,
+  #[serde(
+    deserialize_with = "deserialize_timestamp",
+    serialize_with = "serialize_timestamp"
+  )]
+  pub estimatedShipDate: DateTime<Utc>,
   pub methodCode: String,
   pub postalAddress: PostalAddress,
 }
@@ -39,22 +47,22 @@ pub struct OrderLineItem {
 #[allow(non_snake_case)]
 pub struct Tax {
   pub taxName: Option<String>,
-  pub taxAmount: CurrencyAmount,
+  pub taxAmount: Money,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 #[allow(non_snake_case)]
 pub struct OrderLineChargeItem {
-  pub chargeType: String,
+  pub chargeType: ChargeType,
   pub chargeName: String,
-  pub chargeAmount: CurrencyAmount,
+  pub chargeAmount: Money,
   pub tax: Option<Tax>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 #[allow(non_snake_case)]
 pub struct Quantity {
-  pub unitOfMeasurement: String,
+  pub unitOfMeasurement: UnitOfMeasurement,
   pub amount: String,
 }
 
@@ -68,13 +76,18 @@ pub struct OrderLineCharges {
 #[allow(non_snake_case)]
 pub struct OrderLineTrackingInfoCarrier {
   pub otherCarrier: Option<String>,
-  pub carrier: Option<String>,
+  pub carrier: Option<Carrier>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 #[allow(non_snake_case)]
 pub struct OrderLineTrackingInfo {
-  pub shipDateTime: Option<i64>,
+  #[serde(
+    default,
+    deserialize_with = "deserialize_timestamp_opt",
+    serialize_with = "serialize_timestamp_opt"
+  )]
+  pub shipDateTime: Option<DateTime<Utc>>,
   pub carrierName: OrderLineTrackingInfoCarrier,
   pub methodCode: String,
   pub trackingNumber: Option<String>,
@@ -84,9 +97,10 @@ pub struct OrderLineTrackingInfo {
 #[derive(Debug, Serialize, Deserialize)]
 #[allow(non_snake_case)]
 pub struct OrderLineStatus {
-  pub status: String,
+  pub status: OrderLineStatusKind,
   pub statusQuantity: Quantity,
-  // pub cancellationReason: Option<?>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub cancellationReason: Option<CancellationReason>,
   pub trackingInfo: Option<OrderLineTrackingInfo>,
 }
 
@@ -103,9 +117,69 @@ pub struct OrderLine {
   pub item: OrderLineItem,
   pub charges: OrderLineCharges,
   pub orderLineQuantity: Quantity,
-  pub statusDate: Option<i64>,
+  #[serde(
+    default,
+    deserialize_with = "deserialize_timestamp_opt",
+    serialize_with = "serialize_timestamp_opt"
+  )]
+  pub statusDate: Option<DateTime<Utc>>,
   pub orderLineStatuses: OrderLineStatuss,
-  // pub refund: Option<?>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub refund: Option<OrderLineRefund>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub fulfillment: Option<OrderLineFulfillment>,
+  /// Fields not modeled above
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub struct OrderLineFulfillment {
+  pub fulfillmentOption: Option<String>,
+  pub shipMethod: Option<String>,
+  pub storeId: Option<String>,
+  #[serde(
+    default,
+    deserialize_with = "deserialize_timestamp_opt",
+    serialize_with = "serialize_timestamp_opt"
+  )]
+  pub pickUpDateTime: Option<DateTime<Utc>>,
+  pub pickUpBy: Option<String>,
+  pub shippingProgramType: Option<String>,
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub struct ShipNode {
+  #[serde(rename = "type")]
+  pub nodeType: Option<ShipNodeType>,
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub struct RefundCharge {
+  pub refundReason: RefundReason,
+  pub charge: OrderLineChargeItem,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub struct RefundCharges {
+  pub refundCharge: Vec<RefundCharge>,
+}
+
+/// Refunded charges of an order line, amounts are negative
+#[derive(Debug, Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub struct OrderLineRefund {
+  pub refundId: Option<String>,
+  pub refundComments: Option<String>,
+  pub refundCharges: RefundCharges,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -120,20 +194,123 @@ pub struct Order {
   pub purchaseOrderId: String,
   pub customerOrderId: String,
   pub customerEmailId: Option<String>,
-  pub orderDate: i64,
+  #[serde(
+    deserialize_with = "deserialize_timestamp",
+    serialize_with = "serialize_timestamp"
+  )]
+  pub orderDate: DateTime<Utc>,
   pub shippingInfo: ShippingInformation,
   pub orderLines: OrderLines,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub shipNode: Option<ShipNode>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub orderType: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub originalCustomerOrderID: Option<String>,
+  /// Fields not modeled above
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
 }
 
 // We need a separate WFS type because CA API doesn't return `purchaseOrderId` field for WFS orders
 #[derive(Debug, Serialize, Deserialize)]
 #[allow(non_snake_case)]
 pub struct OrderWFS {
+  /// Only returned by the US API
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub purchaseOrderId: Option<String>,
   pub customerOrderId: String,
   pub customerEmailId: Option<String>,
-  pub orderDate: i64,
+  #[serde(
+    deserialize_with = "deserialize_timestamp",
+    serialize_with = "serialize_timestamp"
+  )]
+  pub orderDate: DateTime<Utc>,
   pub shippingInfo: ShippingInformation,
   pub orderLines: OrderLines,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub shipNode: Option<ShipNode>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub orderType: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub originalCustomerOrderID: Option<String>,
+  /// Fields not modeled above
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+/// Fields shared by `Order` and `OrderWFS`, to handle both in one code path
+pub trait OrderInfo {
+  /// `None` for Canada WFS orders
+  fn get_purchase_order_id(&self) -> Option<&str>;
+  fn get_customer_order_id(&self) -> &str;
+  fn get_order_date(&self) -> DateTime<Utc>;
+  fn get_shipping_info(&self) -> &ShippingInformation;
+  fn get_order_lines(&self) -> &OrderLines;
+  fn get_ship_node(&self) -> Option<&ShipNode>;
+
+  /// Fulfilled by Walmart
+  fn is_wfs(&self) -> bool {
+    self.get_ship_node().and_then(|node| node.nodeType.as_ref())
+      == Some(&ShipNodeType::WFSFulfilled)
+  }
+}
+
+impl OrderInfo for Order {
+  fn get_purchase_order_id(&self) -> Option<&str> {
+    Some(&self.purchaseOrderId)
+  }
+
+  fn get_customer_order_id(&self) -> &str {
+    &self.customerOrderId
+  }
+
+  fn get_order_date(&self) -> DateTime<Utc> {
+    self.orderDate
+  }
+
+  fn get_shipping_info(&self) -> &ShippingInformation {
+    &self.shippingInfo
+  }
+
+  fn get_order_lines(&self) -> &OrderLines {
+    &self.orderLines
+  }
+
+  fn get_ship_node(&self) -> Option<&ShipNode> {
+    self.shipNode.as_ref()
+  }
+}
+
+impl OrderInfo for OrderWFS {
+  fn get_purchase_order_id(&self) -> Option<&str> {
+    self.purchaseOrderId.as_deref()
+  }
+
+  fn get_customer_order_id(&self) -> &str {
+    &self.customerOrderId
+  }
+
+  fn get_order_date(&self) -> DateTime<Utc> {
+    self.orderDate
+  }
+
+  fn get_shipping_info(&self) -> &ShippingInformation {
+    &self.shippingInfo
+  }
+
+  fn get_order_lines(&self) -> &OrderLines {
+    &self.orderLines
+  }
+
+  fn get_ship_node(&self) -> Option<&ShipNode> {
+    self.shipNode.as_ref()
+  }
+
+  // Canada WFS orders have no `shipNode`
+  fn is_wfs(&self) -> bool {
+    true
+  }
 }
 
 #[cfg(test)]
@@ -291,5 +468,108 @@ mod tests {
     let orders: Vec<Order> = serde_json::from_str(&json).unwrap();
     assert_eq!(orders[0].orderLines.orderLine[0].lineNumber, "1");
     assert_eq!(orders[0].orderLines.orderLine[0].item.sku, "edifier-h850");
+    assert_eq!(
+      orders[1].orderLines.orderLine[0]
+        .orderLineStatuses
+        .orderLineStatus[0]
+        .status,
+      OrderLineStatusKind::Cancelled
+    );
+  }
+
+  #[test]
+  fn round_trip_extra_fields() {
+    let json = json!({
+      "purchaseOrderId": "11",
+      "customerOrderId": "12",
+      "customerEmailId": null,
+      "orderDate": 1501903867000i64,
+      "sellerOrderId": "S-11",
+      "orderType": "REGULAR",
+      "originalCustomerOrderID": "10",
+      "shipNode": { "type": "SellerFulfilled", "name": "Main" },
+      "shippingInfo": {
+        "phone": null,
+        "estimatedDeliveryDate": null,
+        "estimatedShipDate": 1502258400000i64,
+        "methodCode": "Standard",
+        "postalAddress": {
+          "name": "Foo Bar",
+          "address1": "7777 Madisoner Ct",
+          "address2": null,
+          "city": "Fake",
+          "state": "VA",
+          "postalCode": "78787",
+          "country": "USA",
+          "addressType": "RESIDENTIAL"
+        }
+      },
+      "orderLines": {
+        "orderLine": [
+          {
+            "lineNumber": "1",
+            "item": { "productName": "Headphones", "sku": "h850" },
+            "charges": {
+              "charge": [
+                {
+                  "chargeType": "PRODUCT",
+                  "chargeName": "ItemPrice",
+                  "chargeAmount": { "currency": "USD", "amount": 39.99 },
+                  "tax": { "taxName": "Tax1", "taxAmount": { "currency": "USD", "amount": 2.5 } }
+                }
+              ]
+            },
+            "orderLineQuantity": { "unitOfMeasurement": "EACH", "amount": "1" },
+            "statusDate": 1502114839000i64,
+            "orderLineStatuses": {
+              "orderLineStatus": [
+                {
+                  "status": "Cancelled",
+                  "statusQuantity": { "unitOfMeasurement": "EACH", "amount": "1" },
+                  "cancellationReason": "CUSTOMER_REQUESTED_SELLER_TO_CANCEL",
+                  "trackingInfo": null
+                }
+              ]
+            },
+            "intentToCancel": "NO",
+            "fulfillment": {
+              "fulfillmentOption": "S2H",
+              "shipMethod": "STANDARD",
+              "storeId": null,
+              "pickUpDateTime": 1502258400000i64,
+              "pickUpBy": null,
+              "shippingProgramType": null
+            }
+          }
+        ]
+      }
+    });
+
+    let order: Order = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(order.orderType.as_ref().unwrap(), "REGULAR");
+    assert_eq!(
+      order.shipNode.as_ref().unwrap().nodeType,
+      Some(ShipNodeType::SellerFulfilled)
+    );
+    assert!(!order.is_wfs());
+    assert_eq!(order.extra["sellerOrderId"], "S-11");
+    let line = &order.orderLines.orderLine[0];
+    assert_eq!(
+      line.orderLineStatuses.orderLineStatus[0].cancellationReason,
+      Some(CancellationReason::CustomerRequestedSellerToCancel)
+    );
+    assert_eq!(
+      line
+        .fulfillment
+        .as_ref()
+        .unwrap()
+        .fulfillmentOption
+        .as_ref()
+        .unwrap(),
+      "S2H"
+    );
+    assert_eq!(line.extra["intentToCancel"], "NO");
+
+    assert_eq!(serde_json::to_value(&order).unwrap(), json);
   }
 }
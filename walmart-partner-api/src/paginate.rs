@@ -0,0 +1,181 @@
+//! Iterators over paged list endpoints
+//!
+//! ```no_run
+//! # use walmart_partner_api::{Client, order::QueryParams};
+//! # fn run(client: &Client) -> walmart_partner_api::result::WalmartResult<()> {
+//! for order in client.orders(&QueryParams::default()).items() {
+//!   println!("{}", order?.purchaseOrderId);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::response::ListResponse;
+use crate::result::*;
+use serde::Serialize;
+
+/// A page of a list endpoint
+pub trait Page {
+  type Item;
+
+  fn into_items(self) -> Vec<Self::Item>;
+}
+
+impl<T: Serialize> Page for ListResponse<T> {
+  type Item = T;
+
+  fn into_items(self) -> Vec<T> {
+    self.elements
+  }
+}
+
+type NextPage<'a, P> = Box<dyn FnMut() -> Option<WalmartResult<P>> + 'a>;
+
+/// Pages of a list endpoint, fetched lazily
+pub struct Paginator<'a, P> {
+  next_page: NextPage<'a, P>,
+}
+
+impl<'a, P: Page + 'a> Paginator<'a, P> {
+  /// `next_page` fetches the next page, or returns `None` after the last page
+  pub fn from_fn<F>(next_page: F) -> Self
+  where
+    F: FnMut() -> Option<WalmartResult<P>> + 'a,
+  {
+    Paginator {
+      next_page: Box::new(next_page),
+    }
+  }
+
+  /// Iterates over pages. Iteration ends after the first error.
+  pub fn pages(self) -> Pages<'a, P> {
+    Pages {
+      paginator: self,
+      done: false,
+    }
+  }
+
+  /// Iterates over the items of all pages. Iteration ends after the first error.
+  pub fn items(self) -> Items<'a, P> {
+    Items {
+      pages: self.pages(),
+      items: vec![].into_iter(),
+    }
+  }
+}
+
+impl<'a, T: Serialize + 'a> Paginator<'a, ListResponse<T>> {
+  /// Follows `nextCursor`, `first` requests the first page and `next` the page of a cursor
+  pub fn cursor<F, N>(mut first: F, mut next: N) -> Self
+  where
+    F: FnMut() -> WalmartResult<ListResponse<T>> + 'a,
+    N: FnMut(&str) -> WalmartResult<ListResponse<T>> + 'a,
+  {
+    let mut cursor: Option<Option<String>> = None;
+    Self::from_fn(move || {
+      let res = match cursor.take() {
+        None => first(),
+        Some(Some(ref next_cursor)) => next(next_cursor),
+        Some(None) => return None,
+      };
+
+      let mut next_cursor = None;
+      if let Ok(ref page) = res {
+        // an empty page would return the same cursor again
+        if !page.elements.is_empty() {
+          next_cursor = page
+            .get_next_cursor()
+            .filter(|c| !c.is_empty())
+            .map(str::to_string);
+        }
+      }
+      cursor = Some(next_cursor);
+      Some(res)
+    })
+  }
+}
+
+pub struct Pages<'a, P> {
+  paginator: Paginator<'a, P>,
+  done: bool,
+}
+
+impl<'a, P> Iterator for Pages<'a, P> {
+  type Item = WalmartResult<P>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.done {
+      return None;
+    }
+    let res = (self.paginator.next_page)();
+    match res {
+      Some(Ok(_)) => {}
+      _ => self.done = true,
+    }
+    res
+  }
+}
+
+pub struct Items<'a, P: Page> {
+  pages: Pages<'a, P>,
+  items: ::std::vec::IntoIter<P::Item>,
+}
+
+impl<'a, P: Page> Iterator for Items<'a, P> {
+  type Item = WalmartResult<P::Item>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      if let Some(item) = self.items.next() {
+        return Some(Ok(item));
+      }
+      match self.pages.next()? {
+        Ok(page) => self.items = page.into_items().into_iter(),
+        Err(err) => return Some(Err(err)),
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::response::ListMeta;
+
+  fn page(elements: Vec<i32>, next_cursor: Option<&str>) -> ListResponse<i32> {
+    ListResponse {
+      meta: Some(ListMeta {
+        totalCount: 5,
+        limit: 2,
+        nextCursor: next_cursor.map(str::to_string),
+      }),
+      elements,
+    }
+  }
+
+  #[test]
+  fn test_cursor() {
+    let paginator = Paginator::cursor(
+      || Ok(page(vec![1, 2], Some("?soIndex=2"))),
+      |cursor| match cursor {
+        "?soIndex=2" => Ok(page(vec![3, 4], Some("?soIndex=4"))),
+        "?soIndex=4" => Ok(page(vec![5], None)),
+        _ => unreachable!(),
+      },
+    );
+    let items: Vec<i32> = paginator.items().map(Result::unwrap).collect();
+    assert_eq!(items, vec![1, 2, 3, 4, 5]);
+  }
+
+  #[test]
+  fn test_error_ends_iteration() {
+    let paginator = Paginator::cursor(
+      || Ok(page(vec![1], Some("?soIndex=1"))),
+      |_| Err(WalmartError::Msg("failed".to_string())),
+    );
+    let items: Vec<_> = paginator.items().collect();
+    assert_eq!(items.len(), 2);
+    assert!(items[0].is_ok());
+    assert!(items[1].is_err());
+  }
+}
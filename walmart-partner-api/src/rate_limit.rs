@@ -0,0 +1,232 @@
+//! Client side rate limiting
+//!
+//! Walmart throttles each API with a token bucket and reports its state in the
+//! `x-current-token-count` and `x-next-replenish-time` response headers.
+//! `RateLimiter` remembers the state per endpoint family and waits (or fails)
+//! before sending a request that would be throttled.
+
+use crate::result::*;
+use chrono::Utc;
+use reqwest::header::HeaderMap;
+use reqwest::{Method, Url};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const HEADER_CURRENT_TOKEN_COUNT: &str = "x-current-token-count";
+const HEADER_NEXT_REPLENISH_TIME: &str = "x-next-replenish-time";
+
+/// What to do when a request would be throttled
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum RateLimitMode {
+  /// Send requests regardless of the token count
+  Disabled,
+  /// Wait until the tokens are replenished
+  #[default]
+  Block,
+  /// Return `WalmartError::RateLimited` immediately
+  Error,
+}
+
+#[derive(Debug)]
+struct Bucket {
+  tokens: i64,
+  replenish_at: Instant,
+}
+
+pub(crate) struct RateLimiter {
+  mode: RateLimitMode,
+  buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+  pub fn new(mode: RateLimitMode) -> Self {
+    RateLimiter {
+      mode,
+      buckets: Mutex::new(HashMap::new()),
+    }
+  }
+
+  /// Takes a token of the endpoint family before sending a request
+  pub fn acquire(&self, method: &Method, url: &Url) -> WalmartResult<()> {
+    if self.mode == RateLimitMode::Disabled {
+      return Ok(());
+    }
+
+    let family = get_endpoint_family(method, url);
+    loop {
+      let wait = {
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = match buckets.get_mut(&family) {
+          Some(bucket) => bucket,
+          // limits are unknown until the first response
+          None => return Ok(()),
+        };
+
+        // the token count is unknown after the replenish time until the next response
+        let now = Instant::now();
+        if bucket.tokens > 0 || bucket.replenish_at <= now {
+          bucket.tokens -= 1;
+          return Ok(());
+        }
+        bucket.replenish_at - now
+      };
+
+      match self.mode {
+        RateLimitMode::Error => {
+          return Err(WalmartError::RateLimited(family, wait));
+        }
+        _ => {
+          debug!("rate limited: family = {}, wait = {:?}", family, wait);
+          ::std::thread::sleep(wait);
+        }
+      }
+    }
+  }
+
+  /// Updates the endpoint family's bucket from response headers
+  pub fn update(&self, method: &Method, url: &Url, headers: &HeaderMap) {
+    if self.mode == RateLimitMode::Disabled {
+      return;
+    }
+
+    let tokens = get_header_i64(headers, HEADER_CURRENT_TOKEN_COUNT);
+    let replenish_time = get_header_i64(headers, HEADER_NEXT_REPLENISH_TIME);
+    if let (Some(tokens), Some(replenish_time)) = (tokens, replenish_time) {
+      let now = Utc::now();
+      let now_ms = now.timestamp() * 1000 + i64::from(now.timestamp_subsec_millis());
+      let replenish_at =
+        Instant::now() + Duration::from_millis(replenish_time.saturating_sub(now_ms).max(0) as u64);
+      let family = get_endpoint_family(method, url);
+      debug!(
+        "rate limit: family = {}, tokens = {}, replenish_at = {}",
+        family, tokens, replenish_time
+      );
+      self.buckets.lock().unwrap().insert(
+        family,
+        Bucket {
+          tokens,
+          replenish_at,
+        },
+      );
+    }
+  }
+}
+
+fn get_header_i64(headers: &HeaderMap, name: &str) -> Option<i64> {
+  headers
+    .get(name)
+    .and_then(|v| v.to_str().ok())
+    .and_then(|v| v.trim().parse().ok())
+}
+
+/// Collections whose next path segment is an id (a purchase order id, sku, feed id...),
+/// except for the listed sub-resources
+const COLLECTIONS: &[(&str, &[&str])] = &[
+  ("orders", &["released", "wfs"]),
+  ("items", &[]),
+  ("inventory", &[]),
+  ("feeds", &[]),
+  ("returns", &[]),
+];
+
+/// Walmart limits each API separately, e.g. `GET /v3/orders/{id}` and `POST /v3/orders/{id}/shipping`.
+/// The segment after a known collection is replaced with `*`, other segments are kept.
+fn get_endpoint_family(method: &Method, url: &Url) -> String {
+  let mut collection: Option<&[&str]> = None;
+  let path = url
+    .path()
+    .split('/')
+    .filter(|seg| !seg.is_empty())
+    .map(|seg| match collection.take() {
+      Some(sub_resources) if !sub_resources.contains(&seg) => "*",
+      _ => {
+        collection = COLLECTIONS
+          .iter()
+          .find(|&&(name, _)| name == seg)
+          .map(|&(_, sub_resources)| sub_resources);
+        seg
+      }
+    })
+    .collect::<Vec<_>>()
+    .join("/");
+  format!("{} /{}", method, path)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn headers(tokens: i64, replenish_in: Duration) -> HeaderMap {
+    let now = Utc::now();
+    let replenish_time = now.timestamp() * 1000
+      + i64::from(now.timestamp_subsec_millis())
+      + replenish_in.as_secs() as i64 * 1000
+      + i64::from(replenish_in.subsec_millis());
+    let mut headers = HeaderMap::new();
+    headers.insert(
+      HEADER_CURRENT_TOKEN_COUNT,
+      tokens.to_string().parse().unwrap(),
+    );
+    headers.insert(
+      HEADER_NEXT_REPLENISH_TIME,
+      replenish_time.to_string().parse().unwrap(),
+    );
+    headers
+  }
+
+  #[test]
+  fn test_get_endpoint_family() {
+    let url = Url::parse("https://marketplace.walmartapis.com/v3/orders/4792701510023/shipping?purchaseOrderId=4792701510023").unwrap();
+    assert_eq!(
+      get_endpoint_family(&Method::POST, &url),
+      "POST /v3/orders/*/shipping"
+    );
+    let url = Url::parse("https://marketplace.walmartapis.com/v3/ca/orders/released").unwrap();
+    assert_eq!(
+      get_endpoint_family(&Method::GET, &url),
+      "GET /v3/ca/orders/released"
+    );
+    for sku in &["ABC-DEF", "SKU12"] {
+      let url = Url::parse(&format!(
+        "https://marketplace.walmartapis.com/v3/items/{}",
+        sku
+      ))
+      .unwrap();
+      assert_eq!(get_endpoint_family(&Method::GET, &url), "GET /v3/items/*");
+    }
+    let url = Url::parse("https://marketplace.walmartapis.com/v3/returns/R1/refund").unwrap();
+    assert_eq!(
+      get_endpoint_family(&Method::POST, &url),
+      "POST /v3/returns/*/refund"
+    );
+  }
+
+  #[test]
+  fn test_rate_limiter_error() {
+    let limiter = RateLimiter::new(RateLimitMode::Error);
+    let url = Url::parse("https://marketplace.walmartapis.com/v3/orders").unwrap();
+    limiter.acquire(&Method::GET, &url).unwrap();
+
+    limiter.update(&Method::GET, &url, &headers(1, Duration::from_secs(60)));
+    limiter.acquire(&Method::GET, &url).unwrap();
+    match limiter.acquire(&Method::GET, &url) {
+      Err(WalmartError::RateLimited(family, _)) => assert_eq!(family, "GET /v3/orders"),
+      other => panic!("unexpected result: {:?}", other),
+    }
+
+    // other families are not affected
+    let url = Url::parse("https://marketplace.walmartapis.com/v3/orders/released").unwrap();
+    limiter.acquire(&Method::GET, &url).unwrap();
+  }
+
+  #[test]
+  fn test_rate_limiter_block() {
+    let limiter = RateLimiter::new(RateLimitMode::Block);
+    let url = Url::parse("https://marketplace.walmartapis.com/v3/feeds").unwrap();
+    limiter.update(&Method::GET, &url, &headers(0, Duration::from_millis(100)));
+    let now = Instant::now();
+    limiter.acquire(&Method::GET, &url).unwrap();
+    assert!(now.elapsed() >= Duration::from_millis(50));
+  }
+}
@@ -1,6 +1,6 @@
 use super::ReportType;
 use crate::result::*;
-use bigdecimal::BigDecimal;
+use crate::Money;
 use csv;
 use std::io::prelude::*;
 use std::io::BufReader;
@@ -85,7 +85,7 @@ impl_from_csv! {
     #[csv(header = "PRODUCT CATEGORY")]
     pub product_category: String,
     #[csv(header = "PRICE")]
-    pub price: BigDecimal,
+    pub price: Money,
     #[csv(header = "CURRENCY")]
     pub currency: String,
     #[csv(header = "PUBLISH STATUS")]
@@ -159,7 +159,9 @@ impl ReportType for ItemReportType {
         format!("Line {}: Parse csv error: {}", i + 1, err).into()
       })?;
       let fields: Vec<&str> = record.into_iter().collect();
-      rows.push(ItemReportRow::from_csv(&headers, &fields)?)
+      let mut row = ItemReportRow::from_csv(&headers, &fields)?;
+      row.price.currency = row.currency.clone();
+      rows.push(row)
     }
 
     Ok(ItemReport { rows })
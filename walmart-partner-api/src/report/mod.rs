@@ -4,7 +4,11 @@ use std::io::{Read, Write};
 mod item;
 
 pub use self::item::{ItemReport, ItemReportRow, ItemReportType};
+#[cfg(feature = "async")]
+use crate::async_client::{AsyncClient, WalmartFuture};
 use crate::client::{Client, Method};
+#[cfg(feature = "async")]
+use futures::{Future, Stream};
 
 pub trait ReportType {
   type Data;
@@ -25,14 +29,52 @@ impl Client {
     let qs = serde_urlencoded::to_string(&GetReportQuery {
       type_: R::report_type(),
     })?;
-    let res = self.send(self.request_json(Method::GET, "/v2/getReport", qs)?)?;
+    let res = self.send_with(|| self.request_json(Method::GET, "/v2/getReport", qs.clone()))?;
     R::deserialize(res)
   }
   pub fn get_report_raw<W: Write>(&self, type_: &str, mut w: W) -> WalmartResult<u64> {
     let qs = serde_urlencoded::to_string(&GetReportQuery { type_ })?;
     let mut res = self
-      .send(self.request_json(Method::GET, "/v2/getReport", qs)?)?
+      .send_with(|| self.request_json(Method::GET, "/v2/getReport", qs.clone()))?
       .error_for_status()?;
     res.copy_to(&mut w).map_err(Into::into)
   }
 }
+
+#[cfg(feature = "async")]
+impl AsyncClient {
+  pub fn get_report<R>(&self) -> WalmartFuture<R::Data>
+  where
+    R: ReportType,
+    R::Data: Send + 'static,
+  {
+    let qs = try_future!(serde_urlencoded::to_string(&GetReportQuery {
+      type_: R::report_type(),
+    }));
+    self.send_parse(
+      self.request_json(Method::GET, "/v2/getReport", qs),
+      |_status, body| R::deserialize(body.as_slice()),
+    )
+  }
+
+  pub fn get_report_raw<W>(&self, type_: &str, mut w: W) -> WalmartFuture<u64>
+  where
+    W: Write + Send + 'static,
+  {
+    let qs = try_future!(serde_urlencoded::to_string(&GetReportQuery { type_ }));
+    let client = self.clone();
+    Box::new(
+      self
+        .request_json(Method::GET, "/v2/getReport", qs)
+        .and_then(move |req| client.send(req))
+        .and_then(|res| res.error_for_status().map_err(Into::into))
+        .and_then(move |res| {
+          res.into_body().from_err().fold(0, move |total, chunk| {
+            w.write_all(&chunk)
+              .map(|_| total + chunk.len() as u64)
+              .map_err(WalmartError::from)
+          })
+        }),
+    )
+  }
+}
@@ -1,4 +1,4 @@
-use crate::result::ApiResponseError;
+use crate::result::{ApiResponseError, WalmartApiErrorDetail};
 use reqwest::{Response, StatusCode};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
@@ -58,36 +58,32 @@ impl JsonMaybe for Response {
     let mut body = String::new();
     match self.read_to_string(&mut body) {
       Err(err) => {
-        return Err(ApiResponseError {
-          status: status.clone(),
-          message: format!("read response: {}", err),
-          body: "".to_owned(),
-        });
+        return Err(ApiResponseError::new(
+          status,
+          format!("read response: {}", err),
+          "".to_owned(),
+        ));
       }
       _ => {}
     }
 
-    if !status.is_success() {
-      return Err(ApiResponseError {
-        message: format!("status not ok: {}", status),
-        status: status.clone(),
-        body: body,
-      });
-    }
+    parse_json_maybe(status, body)
+  }
+}
 
-    match serde_json::from_str::<T>(&body) {
-      Ok(v) => {
-        return Ok(v);
-      }
-      Err(err) => {
-        return Err(ApiResponseError {
-          message: format!("deserialize body: {}", err),
-          status: status.clone(),
-          body: body,
-        });
-      }
-    }
+/// Deserialize a JSON API response body, or return an error if the status is not successful
+pub fn parse_json_maybe<T: DeserializeOwned>(status: StatusCode, body: String) -> Result<T> {
+  if !status.is_success() {
+    return Err(ApiResponseError::new(
+      status,
+      format!("status not ok: {}", status),
+      body,
+    ));
   }
+
+  serde_json::from_str::<T>(&body).map_err(|err| {
+    ApiResponseError::new(status, format!("deserialize body: {}", err), body.clone())
+  })
 }
 
 /// Get `meta` and `elements` from a JSON API response
@@ -126,11 +122,7 @@ where
   }
 
   if !status.is_success() {
-    return Err(ApiResponseError {
-      message: status.to_string(),
-      status: status.clone(),
-      body: body,
-    });
+    return Err(ApiResponseError::new(status, status.to_string(), body));
   }
 
   match serde_json::from_str::<Response>(&body) {
@@ -138,11 +130,11 @@ where
       let value = match res.list.elements.remove(key) {
         Some(value) => value,
         None => {
-          return Err(ApiResponseError {
-            message: format!("key '{}' was not found in resposne", key),
-            status: status.clone(),
-            body: body,
-          });
+          return Err(ApiResponseError::new(
+            status,
+            format!("key '{}' was not found in resposne", key),
+            body,
+          ));
         }
       };
 
@@ -154,24 +146,85 @@ where
           });
         }
         Err(err) => {
-          return Err(ApiResponseError {
-            message: format!("deserialize json response elements: {}", err.to_string()),
-            status: status.clone(),
-            body: body,
-          });
+          return Err(ApiResponseError::new(
+            status,
+            format!("deserialize json response elements: {}", err.to_string()),
+            body,
+          ));
         }
       }
     }
     Err(err) => {
-      return Err(ApiResponseError {
-        message: format!("deserialize json response: {}", err.to_string()),
-        status: status.clone(),
-        body: body,
-      });
+      return Err(ApiResponseError::new(
+        status,
+        format!("deserialize json response: {}", err.to_string()),
+        body,
+      ));
     }
   }
 }
 
+/// Get `meta` and the `key` array from a JSON API response without a `list` wrapper,
+/// e.g. `{"meta": {..}, "returnOrders": [..]}`
+pub fn parse_meta_list_json<T, R>(
+  status: StatusCode,
+  reader: &mut R,
+  key: &str,
+) -> Result<ListResponse<T>>
+where
+  T: Serialize + DeserializeOwned,
+  R: Read,
+{
+  if status == StatusCode::NOT_FOUND {
+    return Ok(ListResponse {
+      meta: None,
+      elements: vec![],
+    });
+  }
+
+  let mut body = String::new();
+  if let Err(err) = reader.read_to_string(&mut body) {
+    return Err(ApiResponseError::new(
+      status,
+      format!("read response: {}", err),
+      body,
+    ));
+  }
+
+  if !status.is_success() {
+    return Err(ApiResponseError::new(status, status.to_string(), body));
+  }
+
+  let mut value: Value = serde_json::from_str(&body).map_err(|err| {
+    ApiResponseError::new(
+      status,
+      format!("deserialize json response: {}", err),
+      body.clone(),
+    )
+  })?;
+  let meta = match value.get_mut("meta").map(Value::take) {
+    Some(Value::Null) | None => None,
+    Some(meta) => Some(serde_json::from_value::<ListMeta>(meta).map_err(|err| {
+      ApiResponseError::new(
+        status,
+        format!("deserialize json response meta: {}", err),
+        body.clone(),
+      )
+    })?),
+  };
+  let elements = match value.get_mut(key).map(Value::take) {
+    Some(Value::Null) | None => vec![],
+    Some(elements) => serde_json::from_value::<Vec<T>>(elements).map_err(|err| {
+      ApiResponseError::new(
+        status,
+        format!("deserialize json response elements: {}", err),
+        body.clone(),
+      )
+    })?,
+  };
+  Ok(ListResponse { meta, elements })
+}
+
 /// Get single object from a JSON API response
 pub fn parse_object_json<T, R>(status: StatusCode, reader: &mut R, key: &str) -> Result<T>
 where
@@ -186,11 +239,7 @@ where
   }
 
   if !status.is_success() {
-    return Err(ApiResponseError {
-      message: status.to_string(),
-      status: status.clone(),
-      body: body,
-    });
+    return Err(ApiResponseError::new(status, status.to_string(), body));
   }
 
   match serde_json::from_str::<BTreeMap<String, T>>(&body) {
@@ -199,23 +248,48 @@ where
         return Ok(value);
       }
       None => {
-        return Err(ApiResponseError {
-          message: format!("key '{}' was not found in resposne", key),
-          status: status.clone(),
-          body: body,
-        });
+        return Err(ApiResponseError::new(
+          status,
+          format!("key '{}' was not found in resposne", key),
+          body,
+        ));
       }
     },
     Err(err) => {
-      return Err(ApiResponseError {
-        message: format!("deserialize json response: {}", err.to_string()),
-        status: status.clone(),
-        body: body,
-      });
+      return Err(ApiResponseError::new(
+        status,
+        format!("deserialize json response: {}", err.to_string()),
+        body,
+      ));
     }
   }
 }
 
+/// Parse the error details of a JSON error response.
+///
+/// APIs wrap the list differently: `{"errors":{"error":[..]}}`, `{"errors":[..]}` or `{"error":[..]}`
+pub fn parse_error_details_json(body: &str) -> Vec<WalmartApiErrorDetail> {
+  let mut value = match serde_json::from_str::<Value>(body) {
+    Ok(value) => value,
+    Err(_) => return vec![],
+  };
+
+  let list = match value.get_mut("errors").map(Value::take) {
+    Some(Value::Object(mut errors)) => errors.remove("error"),
+    Some(list) => Some(list),
+    None => value.get_mut("error").map(Value::take),
+  };
+
+  match list {
+    Some(Value::Array(items)) => items
+      .into_iter()
+      .filter_map(|item| serde_json::from_value(item).ok())
+      .collect(),
+    Some(item @ Value::Object(_)) => serde_json::from_value(item).into_iter().collect(),
+    _ => vec![],
+  }
+}
+
 #[cfg(test)]
 mod test {
   use super::*;
@@ -223,9 +297,9 @@ mod test {
 
   #[test]
   fn test_parse_list_elements_json() {
-    use order::Order;
+    use crate::order::Order;
     let mut r = Cursor::new(include_str!("./order/test_order_list_res.json").to_string());
-    let res = parse_list_elements_json::<Order, _>(StatusCode::Ok, &mut r, "order").unwrap();
+    let res = parse_list_elements_json::<Order, _>(StatusCode::OK, &mut r, "order").unwrap();
     let meta = res.meta.unwrap();
     assert_eq!(meta.totalCount, 66);
     assert_eq!(meta.limit, 10);
@@ -234,9 +308,45 @@ mod test {
 
   #[test]
   fn test_parse_object_json() {
-    use order::Order;
+    use crate::order::Order;
     let mut r = Cursor::new(include_str!("./order/test_order.json").to_string());
-    let res = parse_object_json::<Order, _>(StatusCode::Ok, &mut r, "order").unwrap();
-    assert_eq!(res.shippingInfo.estimatedDeliveryDate, 1485586800000);
+    let res = parse_object_json::<Order, _>(StatusCode::OK, &mut r, "order").unwrap();
+    assert_eq!(
+      res
+        .shippingInfo
+        .estimatedDeliveryDate
+        .map(|date| date.timestamp_millis()),
+      Some(1485586800000)
+    );
+  }
+
+  #[test]
+  fn test_parse_error_details_json() {
+    let body = r#"{"errors":{"error":[{"code":"INVALID_REQUEST_CONTENT.GMP_ORDER_API","field":"data","description":"Invalid request","info":"Request content is invalid.","severity":"ERROR","category":"DATA","causes":[],"errorIdentifiers":{}}]}}"#;
+    let err = ApiResponseError::new(StatusCode::BAD_REQUEST, "".to_string(), body.to_string());
+    assert_eq!(
+      err.errors,
+      vec![WalmartApiErrorDetail {
+        code: "INVALID_REQUEST_CONTENT.GMP_ORDER_API".to_string(),
+        field: Some("data".to_string()),
+        description: Some("Invalid request".to_string()),
+        info: Some("Request content is invalid.".to_string()),
+        severity: Some("ERROR".to_string()),
+        category: Some("DATA".to_string()),
+      }]
+    );
+    assert!(err.find_error("INVALID_REQUEST_CONTENT").is_some());
+    assert!(err.find_error("INVALID_REQUEST").is_none());
+
+    let errors = parse_error_details_json(
+      r#"{"errors":[{"code":"UNAUTHORIZED.GMP_GATEWAY_API","description":"Unauthorized"}]}"#,
+    );
+    assert_eq!(errors[0].code, "UNAUTHORIZED.GMP_GATEWAY_API");
+
+    let errors =
+      parse_error_details_json(r#"{"error":[{"code":"CONTENT_NOT_FOUND.GMP_ITEM_QUERY_API"}]}"#);
+    assert_eq!(errors[0].code, "CONTENT_NOT_FOUND.GMP_ITEM_QUERY_API");
+
+    assert!(parse_error_details_json("Bad Gateway").is_empty());
   }
 }
@@ -1,6 +1,8 @@
+use crate::retry::is_retryable_status;
 use reqwest::StatusCode;
 use std::error;
 use std::fmt;
+use std::time::Duration;
 
 #[derive(Fail, Debug)]
 pub enum WalmartError {
@@ -42,20 +44,39 @@ pub enum WalmartError {
 
   #[fail(display = "unexpected xml: {}", _0)]
   UnexpectedXml(String),
+
+  #[fail(display = "rate limited: {}, tokens are replenished in {:?}", _0, _1)]
+  RateLimited(String, Duration),
 }
 
 impl WalmartError {
+  /// Error details of a Walmart error response
+  pub fn api_errors(&self) -> &[WalmartApiErrorDetail] {
+    match *self {
+      WalmartError::Api(ref err) => &err.errors,
+      _ => &[],
+    }
+  }
+
+  /// Find the first error detail matching `code`, see `WalmartApiErrorDetail::is`
+  pub fn find_api_error(&self, code: &str) -> Option<&WalmartApiErrorDetail> {
+    match *self {
+      WalmartError::Api(ref err) => err.find_error(code),
+      _ => None,
+    }
+  }
+
   pub fn should_try_again(&self) -> bool {
     match *self {
       WalmartError::Reqwest(ref err) => {
         if let Some(status) = err.status() {
-          let code = status.as_u16();
-          // 429 Too Many Requests
-          code == 429 || code == 500 || code == 503
+          is_retryable_status(status)
         } else {
-          false
+          err.is_timeout()
         }
       }
+      WalmartError::Api(ref err) => is_retryable_status(err.status),
+      WalmartError::RateLimited(..) => true,
       _ => false,
     }
   }
@@ -84,11 +105,55 @@ impl_from!(XmlParse(::xmltree::ParseError));
 impl_from!(Csv(::csv::Error));
 impl_from!(InvalidHeaderValue(::reqwest::header::InvalidHeaderValue));
 
+/// An entry of the `errors` list of a Walmart error response
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct WalmartApiErrorDetail {
+  /// e.g. `INVALID_REQUEST_CONTENT.GMP_ORDER_API`
+  pub code: String,
+  pub field: Option<String>,
+  pub description: Option<String>,
+  pub info: Option<String>,
+  pub severity: Option<String>,
+  pub category: Option<String>,
+}
+
+impl WalmartApiErrorDetail {
+  /// Returns `true` if the code is `code`, or `code` followed by the API name,
+  /// e.g. `CONTENT_NOT_FOUND` matches `CONTENT_NOT_FOUND.GMP_ITEM_QUERY_API`
+  pub fn is(&self, code: &str) -> bool {
+    self.code == code || (self.code.starts_with(code) && self.code[code.len()..].starts_with('.'))
+  }
+}
+
 #[derive(Debug)]
 pub struct ApiResponseError {
   pub message: String,
   pub status: StatusCode,
   pub body: String,
+  /// Error details parsed from `body`, empty if `body` is not a Walmart error response
+  pub errors: Vec<WalmartApiErrorDetail>,
+}
+
+impl ApiResponseError {
+  pub fn new(status: StatusCode, message: String, body: String) -> Self {
+    let errors = if status.is_success() {
+      vec![]
+    } else {
+      parse_error_details(&body)
+    };
+    ApiResponseError {
+      message,
+      status,
+      body,
+      errors,
+    }
+  }
+
+  /// Find the first error detail matching `code`, see `WalmartApiErrorDetail::is`
+  pub fn find_error(&self, code: &str) -> Option<&WalmartApiErrorDetail> {
+    self.errors.iter().find(|e| e.is(code))
+  }
 }
 
 impl fmt::Display for ApiResponseError {
@@ -97,7 +162,23 @@ impl fmt::Display for ApiResponseError {
       f,
       "API Error: status = '{}', message = '{}'",
       self.status, self.message
-    )
+    )?;
+    for err in &self.errors {
+      write!(f, ", {}", err.code)?;
+      if let Some(ref description) = err.description {
+        write!(f, ": {}", description)?;
+      }
+    }
+    Ok(())
+  }
+}
+
+fn parse_error_details(body: &str) -> Vec<WalmartApiErrorDetail> {
+  let body = body.trim();
+  if body.starts_with('<') {
+    crate::xml::parse_error_details_xml(body)
+  } else {
+    crate::response::parse_error_details_json(body)
   }
 }
 
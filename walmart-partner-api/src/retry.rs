@@ -0,0 +1,185 @@
+//! Retry policy for failed requests
+//!
+//! Walmart responds with `429 Too Many Requests` when a request is throttled,
+//! and with `500`/`503` on transient server errors.
+
+use chrono::{DateTime, Utc};
+use rand::{thread_rng, Rng};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Method, StatusCode};
+use std::time::Duration;
+
+/// Retry policy of `Client::send`
+///
+/// A `429` means Walmart didn't process the request, so it is retried for every method.
+/// Server and transport errors are only retried for idempotent requests (`GET`, `HEAD`,
+/// `PUT`, `DELETE`, `OPTIONS`) by default, because replaying a `POST` like `ship_order`
+/// or `bulk_upload_xml` may apply it twice.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+  /// Maximum number of attempts, including the first one
+  pub max_attempts: u32,
+  /// Backoff before the first retry, doubled on every following retry
+  pub initial_backoff: Duration,
+  /// Upper bound of the backoff. A `Retry-After` longer than this is not waited for.
+  pub max_backoff: Duration,
+  /// Also retry non-idempotent requests on server and transport errors
+  pub retry_non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+  fn default() -> Self {
+    RetryPolicy {
+      max_attempts: 3,
+      initial_backoff: Duration::from_millis(500),
+      max_backoff: Duration::from_secs(30),
+      retry_non_idempotent: false,
+    }
+  }
+}
+
+impl RetryPolicy {
+  /// A policy that never retries
+  pub fn none() -> Self {
+    RetryPolicy {
+      max_attempts: 1,
+      ..Default::default()
+    }
+  }
+
+  pub(crate) fn should_retry_method(&self, method: &Method) -> bool {
+    self.retry_non_idempotent || is_idempotent(method)
+  }
+
+  /// Returns `true` if a response with this status is worth retrying
+  pub(crate) fn should_retry_status(&self, method: &Method, status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS
+      || (is_retryable_status(status) && self.should_retry_method(method))
+  }
+
+  /// Returns how long to wait before the next attempt, `attempt` starts from 1.
+  ///
+  /// `retry_after` is the value of the `Retry-After` header of the failed response,
+  /// `None` is returned if it exceeds `max_backoff`.
+  pub(crate) fn get_backoff(
+    &self,
+    attempt: u32,
+    retry_after: Option<Duration>,
+  ) -> Option<Duration> {
+    if let Some(retry_after) = retry_after {
+      return if retry_after <= self.max_backoff {
+        Some(retry_after)
+      } else {
+        None
+      };
+    }
+
+    let exp = attempt.saturating_sub(1).min(16);
+    let backoff = self
+      .initial_backoff
+      .checked_mul(1 << exp)
+      .unwrap_or(self.max_backoff)
+      .min(self.max_backoff);
+
+    // equal jitter: half of the backoff is fixed, the other half is random
+    let half = backoff / 2;
+    let jitter_ms = half.as_secs() * 1000 + u64::from(half.subsec_millis());
+    let jitter = if jitter_ms > 0 {
+      Duration::from_millis(thread_rng().gen_range(0, jitter_ms + 1))
+    } else {
+      Duration::from_millis(0)
+    };
+    Some(half + jitter)
+  }
+}
+
+fn is_idempotent(method: &Method) -> bool {
+  *method == Method::GET
+    || *method == Method::HEAD
+    || *method == Method::PUT
+    || *method == Method::DELETE
+    || *method == Method::OPTIONS
+}
+
+/// Returns `true` if a response with this status is worth retrying for an idempotent request
+pub(crate) fn is_retryable_status(status: StatusCode) -> bool {
+  // 429 Too Many Requests
+  status == StatusCode::TOO_MANY_REQUESTS
+    || status == StatusCode::INTERNAL_SERVER_ERROR
+    || status == StatusCode::SERVICE_UNAVAILABLE
+}
+
+/// Parse the `Retry-After` header, either delay-seconds or an HTTP-date
+pub(crate) fn get_retry_after(headers: &HeaderMap) -> Option<Duration> {
+  let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+  if let Ok(secs) = value.parse::<u64>() {
+    return Some(Duration::from_secs(secs));
+  }
+  let date = DateTime::parse_from_rfc2822(value).ok()?;
+  let delay = date.with_timezone(&Utc) - Utc::now();
+  Some(delay.to_std().unwrap_or_else(|_| Duration::from_secs(0)))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_get_retry_after() {
+    let mut headers = HeaderMap::new();
+    assert_eq!(get_retry_after(&headers), None);
+
+    headers.insert(RETRY_AFTER, "5".parse().unwrap());
+    assert_eq!(get_retry_after(&headers), Some(Duration::from_secs(5)));
+
+    headers.insert(
+      RETRY_AFTER,
+      "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap(),
+    );
+    assert_eq!(get_retry_after(&headers), Some(Duration::from_secs(0)));
+
+    headers.insert(RETRY_AFTER, "soon".parse().unwrap());
+    assert_eq!(get_retry_after(&headers), None);
+  }
+
+  #[test]
+  fn test_get_backoff() {
+    let policy = RetryPolicy {
+      initial_backoff: Duration::from_millis(100),
+      max_backoff: Duration::from_millis(1000),
+      ..Default::default()
+    };
+
+    for attempt in 1..10 {
+      let expected = Duration::from_millis((100 << (attempt - 1)).min(1000));
+      let backoff = policy.get_backoff(attempt, None).unwrap();
+      assert!(backoff >= expected / 2 && backoff <= expected);
+    }
+
+    assert_eq!(
+      policy.get_backoff(1, Some(Duration::from_millis(200))),
+      Some(Duration::from_millis(200))
+    );
+    assert_eq!(policy.get_backoff(1, Some(Duration::from_secs(60))), None);
+  }
+
+  #[test]
+  fn test_should_retry_method() {
+    let policy = RetryPolicy::default();
+    assert!(policy.should_retry_method(&Method::GET));
+    assert!(policy.should_retry_method(&Method::PUT));
+    assert!(!policy.should_retry_method(&Method::POST));
+
+    assert!(policy.should_retry_status(&Method::POST, StatusCode::TOO_MANY_REQUESTS));
+    assert!(!policy.should_retry_status(&Method::POST, StatusCode::SERVICE_UNAVAILABLE));
+    assert!(policy.should_retry_status(&Method::GET, StatusCode::SERVICE_UNAVAILABLE));
+    assert!(!policy.should_retry_status(&Method::GET, StatusCode::BAD_REQUEST));
+
+    let policy = RetryPolicy {
+      retry_non_idempotent: true,
+      ..Default::default()
+    };
+    assert!(policy.should_retry_method(&Method::POST));
+    assert!(policy.should_retry_status(&Method::POST, StatusCode::SERVICE_UNAVAILABLE));
+  }
+}
@@ -0,0 +1,162 @@
+use crate::result::*;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use serde_urlencoded;
+
+mod types;
+
+pub use self::types::*;
+#[cfg(feature = "async")]
+use crate::async_client::{body_to_string, AsyncClient, WalmartFuture};
+use crate::client::{Client, Method};
+use crate::paginate::Paginator;
+#[cfg(feature = "async")]
+use crate::response::parse_json_maybe;
+use crate::response::{parse_meta_list_json, JsonMaybe, ListResponse};
+#[cfg(feature = "async")]
+use futures::Future;
+
+/// Query parameters for `get_all_returns`
+#[derive(Debug, Serialize, Default)]
+#[allow(non_snake_case)]
+pub struct ReturnsQueryParams {
+  pub returnOrderId: Option<String>,
+  pub customerEmailId: Option<String>,
+  pub customerOrderId: Option<String>,
+  pub status: Option<String>,
+  pub returnCreationStartDate: Option<DateTime<Utc>>,
+  pub returnCreationEndDate: Option<DateTime<Utc>>,
+  pub returnLastModifiedStartDate: Option<DateTime<Utc>>,
+  pub returnLastModifiedEndDate: Option<DateTime<Utc>>,
+  pub limit: Option<i32>,
+}
+
+#[derive(Debug, Clone)]
+#[allow(non_snake_case)]
+pub struct ReturnRefundLine {
+  pub returnOrderLineNumber: i32,
+  pub quantity: u32,
+}
+
+fn get_refund_return_body(customer_order_id: &str, lines: &[ReturnRefundLine]) -> Value {
+  let line_values: Vec<_> = lines
+    .iter()
+    .map(|line| {
+      json!({
+        "returnOrderLineNumber": line.returnOrderLineNumber,
+        "quantity": {
+          "unitOfMeasure": "EACH",
+          "measurementValue": line.quantity,
+        }
+      })
+    })
+    .collect();
+  json!({
+    "customerOrderId": customer_order_id,
+    "refundLines": line_values,
+  })
+}
+
+pub type ReturnOrderList = ListResponse<ReturnOrder>;
+
+impl Client {
+  pub fn get_all_returns(&self, params: &ReturnsQueryParams) -> WalmartResult<ReturnOrderList> {
+    let qs = serde_urlencoded::to_string(params)?;
+    let mut res = self.send_with(|| self.request_json(Method::GET, "/v3/returns", qs.clone()))?;
+    parse_meta_list_json(res.status(), &mut res, "returnOrders").map_err(Into::into)
+  }
+
+  pub fn get_all_returns_by_next_cursor(
+    &self,
+    next_cursor: &str,
+  ) -> WalmartResult<ReturnOrderList> {
+    let qs = next_cursor.trim_start_matches('?');
+    let mut res = self.send_with(|| self.request_json(Method::GET, "/v3/returns", qs))?;
+    parse_meta_list_json(res.status(), &mut res, "returnOrders").map_err(Into::into)
+  }
+
+  /// Iterates over all returns matching `params` by `nextCursor`
+  pub fn returns<'a>(&'a self, params: &'a ReturnsQueryParams) -> Paginator<'a, ReturnOrderList> {
+    Paginator::cursor(
+      move || self.get_all_returns(params),
+      move |cursor| self.get_all_returns_by_next_cursor(cursor),
+    )
+  }
+
+  pub fn get_return(&self, return_order_id: &str) -> WalmartResult<ReturnOrder> {
+    let list = self.get_all_returns(&ReturnsQueryParams {
+      returnOrderId: Some(return_order_id.to_string()),
+      ..Default::default()
+    })?;
+    list
+      .elements
+      .into_iter()
+      .find(|order| order.returnOrderId == return_order_id)
+      .ok_or_else(|| format!("return order {} was not found", return_order_id).into())
+  }
+
+  pub fn refund_return(
+    &self,
+    return_order_id: &str,
+    customer_order_id: &str,
+    lines: &[ReturnRefundLine],
+  ) -> WalmartResult<ReturnRefund> {
+    let body = get_refund_return_body(customer_order_id, lines);
+    let path = format!("/v3/returns/{}/refund", return_order_id);
+    self
+      .send_with(|| {
+        self
+          .request_json(Method::POST, &path, ())
+          .map(|req| req.json(&body))
+      })?
+      .json_maybe::<ReturnRefund>()
+      .map_err(Into::into)
+  }
+}
+
+#[cfg(feature = "async")]
+impl AsyncClient {
+  pub fn get_all_returns(&self, params: &ReturnsQueryParams) -> WalmartFuture<ReturnOrderList> {
+    let qs = try_future!(serde_urlencoded::to_string(params));
+    self.send_parse(
+      self.request_json(Method::GET, "/v3/returns", qs),
+      |status, body| {
+        parse_meta_list_json(status, &mut body.as_slice(), "returnOrders").map_err(Into::into)
+      },
+    )
+  }
+
+  pub fn get_all_returns_by_next_cursor(
+    &self,
+    next_cursor: &str,
+  ) -> WalmartFuture<ReturnOrderList> {
+    self.send_parse(
+      self.request_json(
+        Method::GET,
+        "/v3/returns",
+        next_cursor.trim_start_matches('?'),
+      ),
+      |status, body| {
+        parse_meta_list_json(status, &mut body.as_slice(), "returnOrders").map_err(Into::into)
+      },
+    )
+  }
+
+  pub fn refund_return(
+    &self,
+    return_order_id: &str,
+    customer_order_id: &str,
+    lines: &[ReturnRefundLine],
+  ) -> WalmartFuture<ReturnRefund> {
+    let body = get_refund_return_body(customer_order_id, lines);
+    let path = format!("/v3/returns/{}/refund", return_order_id);
+    self.send_parse(
+      Box::new(
+        self
+          .request_json(Method::POST, &path, ())
+          .map(move |req| req.json(&body)),
+      ),
+      |status, body| parse_json_maybe(status, body_to_string(status, body)?).map_err(Into::into),
+    )
+  }
+}
@@ -0,0 +1,146 @@
+use crate::money::{deserialize_amount, serialize_amount};
+use crate::Money;
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use serde_json::{Map, Value};
+
+/// Amount of the returns API, which differs from `Money` in field names
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub struct ReturnAmount {
+  #[serde(
+    deserialize_with = "deserialize_amount",
+    serialize_with = "serialize_amount"
+  )]
+  pub currencyAmount: BigDecimal,
+  pub currencyUnit: String,
+}
+
+impl ReturnAmount {
+  pub fn to_money(&self) -> Money {
+    Money::new(&self.currencyUnit, self.currencyAmount.clone())
+  }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub struct CustomerName {
+  pub firstName: Option<String>,
+  pub lastName: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub struct ReturnQuantity {
+  pub unitOfMeasure: String,
+  pub measurementValue: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub struct ReturnItem {
+  pub sku: String,
+  pub productName: Option<String>,
+  pub condition: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub struct ReturnOrderLine {
+  pub returnOrderLineNumber: i32,
+  pub salesOrderLineNumber: Option<i32>,
+  pub returnReason: Option<String>,
+  pub purchaseOrderId: Option<String>,
+  pub purchaseOrderLineNumber: Option<i32>,
+  pub item: ReturnItem,
+  pub unitPrice: Option<ReturnAmount>,
+  pub quantity: ReturnQuantity,
+  pub refundedQty: Option<f64>,
+  pub status: Option<String>,
+  pub statusTime: Option<DateTime<Utc>>,
+  pub currentRefundStatus: Option<String>,
+  /// Fields not modeled above
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub struct ReturnOrder {
+  pub returnOrderId: String,
+  pub customerEmailId: Option<String>,
+  pub customerName: Option<CustomerName>,
+  pub customerOrderId: String,
+  pub returnOrderDate: Option<DateTime<Utc>>,
+  pub returnByDate: Option<DateTime<Utc>>,
+  pub refundMode: Option<String>,
+  pub totalRefundAmount: Option<ReturnAmount>,
+  pub returnOrderLines: Vec<ReturnOrderLine>,
+  /// Fields not modeled above
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub struct ReturnRefundLineStatus {
+  pub returnOrderLineNumber: i32,
+  pub refundStatus: Option<String>,
+}
+
+/// Response of `refund_return`
+#[derive(Debug, Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub struct ReturnRefund {
+  pub returnOrderId: String,
+  pub customerOrderId: String,
+  pub refundLines: Vec<ReturnRefundLineStatus>,
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn deserialize() {
+    let json = r##"
+      {
+        "returnOrderId": "77",
+        "customerEmailId": "1@relay.walmart.com",
+        "customerName": { "firstName": "Foo", "lastName": "Bar" },
+        "customerOrderId": "12",
+        "returnOrderDate": "2020-03-24T05:02:26.000Z",
+        "returnByDate": "2020-04-23T05:02:26.000Z",
+        "refundMode": "POST_RECEIPT",
+        "totalRefundAmount": { "currencyAmount": 39.99, "currencyUnit": "USD" },
+        "returnLineGroups": [],
+        "returnOrderLines": [
+          {
+            "returnOrderLineNumber": 1,
+            "salesOrderLineNumber": 1,
+            "returnReason": "DAMAGED_ITEM",
+            "purchaseOrderId": "11",
+            "purchaseOrderLineNumber": 1,
+            "isReturnForException": false,
+            "item": { "sku": "edifier-h850", "productName": "Headphones", "condition": "NEW" },
+            "unitPrice": { "currencyAmount": 39.99, "currencyUnit": "USD" },
+            "quantity": { "unitOfMeasure": "EACH", "measurementValue": 1 },
+            "refundedQty": 0,
+            "status": "INITIATED",
+            "statusTime": "2020-03-24T05:02:26.000Z",
+            "currentRefundStatus": "REFUND_PENDING"
+          }
+        ]
+      }
+    "##;
+
+    let order: ReturnOrder = serde_json::from_str(json).unwrap();
+    assert_eq!(order.returnOrderId, "77");
+    assert_eq!(
+      order.totalRefundAmount.unwrap().to_money().to_string(),
+      "39.99 USD"
+    );
+    assert_eq!(order.returnOrderLines[0].item.sku, "edifier-h850");
+    assert!(order.extra.contains_key("returnLineGroups"));
+  }
+}
@@ -0,0 +1,140 @@
+//! Storage of Token API bearer tokens
+//!
+//! A token is valid for 15 minutes. Sharing it through a `TokenStore` lets short-lived
+//! processes like the CLI reuse it instead of calling `/v3/token` every time.
+
+use crate::result::*;
+use crate::utils::{read_json_file, write_json_file};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+/// A bearer token returned by the Token API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredToken {
+  pub access_token: String,
+  pub expires_at: DateTime<Utc>,
+}
+
+/// Where `Client` reads and writes bearer tokens, keyed by client id
+pub trait TokenStore: Send + Sync {
+  fn get(&self, client_id: &str) -> WalmartResult<Option<StoredToken>>;
+  fn set(&self, client_id: &str, token: &StoredToken) -> WalmartResult<()>;
+  fn remove(&self, client_id: &str) -> WalmartResult<()>;
+}
+
+/// Keeps tokens in process memory, the default store
+#[derive(Debug, Default)]
+pub struct MemoryTokenStore {
+  tokens: RwLock<HashMap<String, StoredToken>>,
+}
+
+impl MemoryTokenStore {
+  pub fn new() -> Self {
+    Default::default()
+  }
+}
+
+impl TokenStore for MemoryTokenStore {
+  fn get(&self, client_id: &str) -> WalmartResult<Option<StoredToken>> {
+    Ok(self.tokens.read().unwrap().get(client_id).cloned())
+  }
+
+  fn set(&self, client_id: &str, token: &StoredToken) -> WalmartResult<()> {
+    self
+      .tokens
+      .write()
+      .unwrap()
+      .insert(client_id.to_string(), token.clone());
+    Ok(())
+  }
+
+  fn remove(&self, client_id: &str) -> WalmartResult<()> {
+    self.tokens.write().unwrap().remove(client_id);
+    Ok(())
+  }
+}
+
+/// Keeps tokens in a JSON file shared by processes.
+///
+/// The file is replaced atomically on write, concurrent writers may overwrite
+/// each other's token, which only causes an extra token request.
+#[derive(Debug)]
+pub struct FileTokenStore {
+  path: PathBuf,
+}
+
+impl FileTokenStore {
+  pub fn new<P: AsRef<Path>>(path: P) -> Self {
+    FileTokenStore {
+      path: path.as_ref().to_path_buf(),
+    }
+  }
+
+  fn read(&self) -> WalmartResult<HashMap<String, StoredToken>> {
+    read_json_file(&self.path)
+  }
+
+  fn write(&self, tokens: &HashMap<String, StoredToken>) -> WalmartResult<()> {
+    write_json_file(&self.path, tokens)
+  }
+}
+
+impl TokenStore for FileTokenStore {
+  fn get(&self, client_id: &str) -> WalmartResult<Option<StoredToken>> {
+    Ok(self.read()?.remove(client_id))
+  }
+
+  fn set(&self, client_id: &str, token: &StoredToken) -> WalmartResult<()> {
+    // a corrupted file is overwritten
+    let mut tokens = self.read().unwrap_or_default();
+    tokens.insert(client_id.to_string(), token.clone());
+    self.write(&tokens)
+  }
+
+  fn remove(&self, client_id: &str) -> WalmartResult<()> {
+    let mut tokens = self.read().unwrap_or_default();
+    if tokens.remove(client_id).is_some() {
+      self.write(&tokens)?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use tempfile::NamedTempFile;
+
+  #[test]
+  fn test_file_token_store() {
+    let file = NamedTempFile::new().unwrap();
+    let store = FileTokenStore::new(file.path());
+    assert!(store.get("a").unwrap().is_none());
+
+    let token = StoredToken {
+      access_token: "token_a".to_string(),
+      expires_at: Utc::now(),
+    };
+    store.set("a", &token).unwrap();
+    store
+      .set(
+        "b",
+        &StoredToken {
+          access_token: "token_b".to_string(),
+          expires_at: Utc::now(),
+        },
+      )
+      .unwrap();
+
+    let other = FileTokenStore::new(file.path());
+    let stored = other.get("a").unwrap().unwrap();
+    assert_eq!(stored.access_token, "token_a");
+    assert_eq!(stored.expires_at, token.expires_at);
+
+    other.remove("a").unwrap();
+    assert!(store.get("a").unwrap().is_none());
+    assert_eq!(store.get("b").unwrap().unwrap().access_token, "token_b");
+  }
+}
@@ -1,7 +1,12 @@
+use crate::result::*;
 use chrono::{DateTime, TimeZone, Utc};
-use serde::de;
-use serde::Deserializer;
+use serde::de::{self, DeserializeOwned};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::Path;
+use tempfile::NamedTempFile;
 
 /// Walmart serialize Date to a milliseconds since January 1, 1970 0:00:00 UTC,
 /// It's not a standard unix timestamp, so we need to impl custom unserialize
@@ -22,6 +27,16 @@ impl<'de> de::Visitor<'de> for TimestampVistor {
       .single()
       .ok_or_else(|| E::custom(format!("invalid timestamp value `{}`", v)))
   }
+
+  fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+  where
+    E: de::Error,
+  {
+    Utc
+      .timestamp_millis_opt(v)
+      .single()
+      .ok_or_else(|| E::custom(format!("invalid timestamp value `{}`", v)))
+  }
 }
 
 pub fn deserialize_timestamp<'de, D>(d: D) -> Result<DateTime<Utc>, D::Error>
@@ -31,6 +46,66 @@ where
   d.deserialize_any(TimestampVistor)
 }
 
+/// Serializes to milliseconds, the reverse of `deserialize_timestamp`
+pub fn serialize_timestamp<S>(date: &DateTime<Utc>, s: S) -> Result<S::Ok, S::Error>
+where
+  S: Serializer,
+{
+  s.serialize_i64(date.timestamp_millis())
+}
+
+/// `deserialize_timestamp` for optional fields, use with `#[serde(default)]`
+pub fn deserialize_timestamp_opt<'de, D>(d: D) -> Result<Option<DateTime<Utc>>, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  #[derive(Deserialize)]
+  struct Timestamp(#[serde(deserialize_with = "deserialize_timestamp")] DateTime<Utc>);
+
+  Option::<Timestamp>::deserialize(d).map(|v| v.map(|v| v.0))
+}
+
+pub fn serialize_timestamp_opt<S>(date: &Option<DateTime<Utc>>, s: S) -> Result<S::Ok, S::Error>
+where
+  S: Serializer,
+{
+  match *date {
+    Some(ref date) => s.serialize_some(&date.timestamp_millis()),
+    None => s.serialize_none(),
+  }
+}
+
+/// Reads a JSON file of a store, a missing or empty file is `T::default()`
+pub fn read_json_file<T>(path: &Path) -> WalmartResult<T>
+where
+  T: DeserializeOwned + Default,
+{
+  let content = match fs::read_to_string(path) {
+    Ok(content) => content,
+    Err(ref err) if err.kind() == ErrorKind::NotFound => return Ok(T::default()),
+    Err(err) => return Err(err.into()),
+  };
+  if content.trim().is_empty() {
+    return Ok(T::default());
+  }
+  serde_json::from_str(&content)
+    .map_err(|err| WalmartError::Msg(format!("parse file '{}': {}", path.display(), err)))
+}
+
+/// Replaces a JSON file of a store atomically
+pub fn write_json_file<T: Serialize>(path: &Path, value: &T) -> WalmartResult<()> {
+  let content = serde_json::to_string_pretty(value)
+    .map_err(|err| WalmartError::Msg(format!("serialize '{}': {}", path.display(), err)))?;
+  let dir = match path.parent() {
+    Some(dir) if !dir.as_os_str().is_empty() => dir,
+    _ => Path::new("."),
+  };
+  let mut file = NamedTempFile::new_in(dir)?;
+  file.write_all(content.as_bytes())?;
+  file.persist(path).map_err(|err| err.error)?;
+  Ok(())
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -51,4 +126,30 @@ mod tests {
       "2017-08-12".to_string()
     );
   }
+
+  #[test]
+  fn test_timestamp_opt() {
+    use serde_json;
+
+    #[derive(Serialize, Deserialize)]
+    struct T {
+      #[serde(
+        default,
+        deserialize_with = "deserialize_timestamp_opt",
+        serialize_with = "serialize_timestamp_opt"
+      )]
+      date: Option<DateTime<Utc>>,
+    }
+
+    let t: T = serde_json::from_str(r##"{"date":1502506180690}"##).unwrap();
+    assert_eq!(t.date.unwrap().timestamp_millis(), 1502506180690);
+    assert_eq!(
+      serde_json::to_string(&t).unwrap(),
+      r##"{"date":1502506180690}"##
+    );
+    let t: T = serde_json::from_str(r##"{"date":null}"##).unwrap();
+    assert!(t.date.is_none());
+    let t: T = serde_json::from_str("{}").unwrap();
+    assert!(t.date.is_none());
+  }
 }
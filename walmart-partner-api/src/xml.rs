@@ -1,5 +1,5 @@
 use crate::result::*;
-use reqwest::Response;
+use reqwest::{Response, StatusCode};
 pub use xmltree::Element;
 
 pub trait FromXmlElement: Sized {
@@ -24,19 +24,31 @@ where
   T: FromXmlElement,
 {
   pub fn from_res(res: &mut Response) -> WalmartResult<Self> {
-    use std::io::Cursor;
-
     let status = res.status();
-    let text = res.text().map_err(|err| ApiResponseError {
-      message: format!("get response text: {}", err.to_string()),
-      status: status.clone(),
-      body: "".to_string(),
+    let text = res.text().map_err(|err| {
+      ApiResponseError::new(
+        status,
+        format!("get response text: {}", err.to_string()),
+        "".to_string(),
+      )
     })?;
 
-    let elem = Element::parse(Cursor::new(text.as_bytes())).map_err(|err| ApiResponseError {
-      message: format!("parse response xml: {}", err.to_string()),
-      status: status.clone(),
-      body: text.clone(),
+    Self::from_text(status, text)
+  }
+
+  pub fn from_text(status: StatusCode, text: String) -> WalmartResult<Self> {
+    use std::io::Cursor;
+
+    if !status.is_success() {
+      return Err(ApiResponseError::new(status, format!("status not ok: {}", status), text).into());
+    }
+
+    let elem = Element::parse(Cursor::new(text.as_bytes())).map_err(|err| {
+      ApiResponseError::new(
+        status,
+        format!("parse response xml: {}", err.to_string()),
+        text.clone(),
+      )
     })?;
 
     let inner = T::from_xml_element(elem)?;
@@ -65,3 +77,70 @@ impl GetChildText for Element {
     self.get_child(name).and_then(|c| c.text.clone())
   }
 }
+
+/// Parse the error details of a XML error response, e.g.
+/// `<ns2:errors><ns2:error><ns2:code>..</ns2:code></ns2:error></ns2:errors>`
+pub fn parse_error_details_xml(body: &str) -> Vec<WalmartApiErrorDetail> {
+  use std::io::Cursor;
+
+  let elem = match Element::parse(Cursor::new(body.as_bytes())) {
+    Ok(elem) => elem,
+    Err(_) => return vec![],
+  };
+
+  let errors = if elem.name == "errors" {
+    &elem
+  } else {
+    match elem.get_child("errors") {
+      Some(errors) => errors,
+      None => return vec![],
+    }
+  };
+
+  errors
+    .children
+    .iter()
+    .filter(|c| c.name == "error")
+    .map(|c| WalmartApiErrorDetail {
+      code: c.get_child_text_or_default("code"),
+      field: c.get_child_text("field"),
+      description: c.get_child_text("description"),
+      info: c.get_child_text("info"),
+      severity: c.get_child_text("severity"),
+      category: c.get_child_text("category"),
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_error_details_xml() {
+    let body = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<ns2:errors xmlns:ns2="http://walmart.com/">
+  <ns2:error>
+    <ns2:code>CONTENT_NOT_FOUND.GMP_ITEM_QUERY_API</ns2:code>
+    <ns2:field>sku</ns2:field>
+    <ns2:description>Item not found</ns2:description>
+    <ns2:info>Requested content could not be found.</ns2:info>
+    <ns2:severity>ERROR</ns2:severity>
+    <ns2:category>APPLICATION</ns2:category>
+  </ns2:error>
+</ns2:errors>"#;
+    let err = ApiResponseError::new(StatusCode::NOT_FOUND, "".to_string(), body.to_string());
+    assert_eq!(
+      err.errors,
+      vec![WalmartApiErrorDetail {
+        code: "CONTENT_NOT_FOUND.GMP_ITEM_QUERY_API".to_string(),
+        field: Some("sku".to_string()),
+        description: Some("Item not found".to_string()),
+        info: Some("Requested content could not be found.".to_string()),
+        severity: Some("ERROR".to_string()),
+        category: Some("APPLICATION".to_string()),
+      }]
+    );
+    assert!(err.find_error("CONTENT_NOT_FOUND").is_some());
+  }
+}
@@ -0,0 +1,81 @@
+//! Replays recorded API interactions from `tests/cassettes`.
+//!
+//! To re-record a cassette against the live API, build the client with
+//! `Cassette::record` and real credentials instead of `Cassette::replay`.
+
+use std::io::Cursor;
+use walmart_partner_api::feed::GetAllFeedStatusesQuery;
+use walmart_partner_api::order::ReleasedQueryParams;
+use walmart_partner_api::report::ItemReportType;
+use walmart_partner_api::{Cassette, Client, WalmartCredential, WalmartMarketplace};
+
+fn client(cassette: &str) -> Client {
+  let path = format!(
+    "{}/tests/cassettes/{}",
+    env!("CARGO_MANIFEST_DIR"),
+    cassette
+  );
+  Client::builder(
+    WalmartMarketplace::USA,
+    WalmartCredential::TokenApi {
+      client_id: "client_id".to_string(),
+      client_secret: "client_secret".to_string(),
+    },
+  )
+  .cassette(Cassette::replay(path).unwrap())
+  .build()
+  .unwrap()
+}
+
+#[test]
+fn test_orders() {
+  let client = client("orders.json");
+
+  let list = client
+    .get_all_released_orders(&ReleasedQueryParams {
+      limit: Some(10),
+      ..Default::default()
+    })
+    .unwrap();
+  assert_eq!(list.get_total_count(), Some(66));
+  assert_eq!(list.elements.len(), 2);
+  assert_eq!(list.elements[0].purchaseOrderId, "11");
+
+  let order = client.get_order("1577684050862").unwrap();
+  assert_eq!(order.purchaseOrderId, "1577684050862");
+
+  let order = client.ack_order("1577684050862").unwrap();
+  assert_eq!(order.purchaseOrderId, "1577684050862");
+
+  let err = client.get_order("404").unwrap_err();
+  assert!(err.find_api_error("CONTENT_NOT_FOUND").is_some());
+}
+
+#[test]
+fn test_feeds() {
+  let client = client("feeds.json");
+
+  let statuses = client
+    .get_all_feed_statuses(&GetAllFeedStatusesQuery {
+      limit: Some(50),
+      ..Default::default()
+    })
+    .unwrap();
+  assert_eq!(statuses.totalResults, 1);
+  assert_eq!(statuses.results.feed[0].feedStatus, "PROCESSED");
+
+  let ack = client
+    .bulk_upload_xml("item", Cursor::new(b"<MPItemFeed/>".to_vec()))
+    .unwrap();
+  assert_eq!(ack.feedId, "117E39F0B7654B08A059457FB6E803FF@AQYBAAA");
+}
+
+#[test]
+fn test_reports() {
+  let client = client("reports.json");
+
+  let report = client.get_report::<ItemReportType>().unwrap();
+  assert_eq!(report.rows.len(), 1);
+  assert_eq!(report.rows[0].sku, "SKU-1");
+  assert_eq!(report.rows[0].inventory_count, Some(5));
+}